@@ -0,0 +1,196 @@
+//! `AbiType` derivation for structs encoded as Solidity tuples
+//! and fieldless enums encoded as `uint8`
+
+use proc_macro2::{self, Span};
+use syn;
+
+pub fn impl_abi_type(input: syn::DeriveInput) -> proc_macro2::TokenStream {
+	assert!(
+		input.generics.params.is_empty(),
+		"#[derive(AbiType)] does not support generic types"
+	);
+	match input.data {
+		syn::Data::Struct(ref data) => impl_struct(&input.ident, data),
+		syn::Data::Enum(ref data) => impl_enum(&input.ident, data),
+		syn::Data::Union(_) => panic!("#[derive(AbiType)] is not supported for unions"),
+	}
+}
+
+fn impl_struct(name: &syn::Ident, data: &syn::DataStruct) -> proc_macro2::TokenStream {
+	let fields: Vec<&syn::Field> = data.fields.iter().collect();
+	assert!(
+		!fields.is_empty(),
+		"#[derive(AbiType)] requires at least one field"
+	);
+
+	let types: &Vec<&syn::Type> = &fields.iter().map(|field| &field.ty).collect();
+	let accessors: &Vec<proc_macro2::TokenStream> = &fields
+		.iter()
+		.enumerate()
+		.map(|(idx, field)| match field.ident {
+			Some(ref ident) => quote! { #ident },
+			None => {
+				let idx = syn::Index::from(idx);
+				quote! { #idx }
+			}
+		})
+		.collect();
+	// members of tuple structs have no names
+	let names = fields
+		.iter()
+		.filter_map(|field| field.ident.as_ref().map(|ident| ident.to_string()));
+
+	quote! {
+		const _: () = {
+			extern crate owasm_abi;
+
+			impl owasm_abi::eth::AbiType for #name {
+				fn decode(stream: &mut owasm_abi::eth::Stream) -> Result<Self, owasm_abi::eth::Error> {
+					Ok(#name {
+						#(#accessors: stream.pop::<#types>()?,)*
+					})
+				}
+
+				fn encode(self, sink: &mut owasm_abi::eth::Sink) {
+					if <Self as owasm_abi::eth::AbiType>::IS_FIXED {
+						#(sink.push(self.#accessors);)*
+					} else {
+						// offsets of dynamic members are relative to the start of the tuple
+						let mut nested_sink = owasm_abi::eth::Sink::new(
+							0 #(+ <#types as owasm_abi::eth::AbiType>::HEAD_WORDS)*
+						);
+						#(nested_sink.push(self.#accessors);)*
+						nested_sink.drain_to(sink.preamble_mut());
+					}
+				}
+
+				const IS_FIXED: bool = true #(&& <#types as owasm_abi::eth::AbiType>::IS_FIXED)*;
+
+				const HEAD_WORDS: usize = if <Self as owasm_abi::eth::AbiType>::IS_FIXED {
+					0 #(+ <#types as owasm_abi::eth::AbiType>::HEAD_WORDS)*
+				} else {
+					1
+				};
+
+				const CANONICAL: owasm_abi::eth::Canonical = owasm_abi::eth::Canonical::Tuple {
+					members: &[#(<#types as owasm_abi::eth::AbiType>::CANONICAL),*],
+					names: &[#(#names),*],
+				};
+			}
+
+			impl owasm_abi::eth::EncodeInto for #name {
+				type Owned = #name;
+
+				fn encode_into(
+					&self,
+					sink: &mut owasm_abi::eth::BufferSink,
+				) -> Result<(), owasm_abi::eth::BufferOverflow> {
+					// offsets of dynamic members are relative to the start of the tuple
+					sink.push_scope(0 #(+ <#types as owasm_abi::eth::AbiType>::HEAD_WORDS)*, |sink| {
+						#(sink.push(&self.#accessors)?;)*
+						Ok(())
+					})
+				}
+
+				fn encode_in_place(&self, encoded: &mut owasm_abi::types::Vec<u8>) {
+					#(owasm_abi::eth::EncodeInto::encode_in_place(&self.#accessors, encoded);)*
+				}
+			}
+		};
+	}
+}
+
+/// Derives `AbiType` for a fieldless enum.
+///
+/// Variants are encoded as their position in declaration order,
+/// regardless of explicit discriminants, like Solidity enums.
+fn impl_enum(name: &syn::Ident, data: &syn::DataEnum) -> proc_macro2::TokenStream {
+	assert!(
+		!data.variants.is_empty(),
+		"#[derive(AbiType)] requires at least one variant"
+	);
+	assert!(
+		data.variants.len() <= 256,
+		"#[derive(AbiType)] supports at most 256 enum variants"
+	);
+	assert!(
+		data.variants.iter().all(|variant| variant.fields == syn::Fields::Unit),
+		"#[derive(AbiType)] is only supported for enums without fields"
+	);
+	let variants: &Vec<proc_macro2::TokenStream> = &data.variants
+		.iter()
+		.map(|variant| {
+			let ident = &variant.ident;
+			quote! { #name::#ident }
+		})
+		.collect();
+	let indices: &Vec<syn::Lit> = &(0..variants.len())
+		.map(|idx| syn::Lit::Int(syn::LitInt::new(idx as u64, syn::IntSuffix::U8, Span::call_site())))
+		.collect();
+	let variants_count = variants.len() as u64;
+
+	quote! {
+		const _: () = {
+			extern crate owasm_abi;
+
+			impl owasm_abi::eth::AbiType for #name {
+				fn decode(stream: &mut owasm_abi::eth::Stream) -> Result<Self, owasm_abi::eth::Error> {
+					let index = stream.pop::<owasm_abi::types::U256>()?;
+					if index >= owasm_abi::types::U256::from(#variants_count) {
+						return Err(owasm_abi::eth::Error::InvalidEnum);
+					}
+					#[allow(unreachable_patterns)]
+					match index.low_u64() as u8 {
+						#(#indices => Ok(#variants),)*
+						_ => Err(owasm_abi::eth::Error::InvalidEnum),
+					}
+				}
+
+				fn encode(self, sink: &mut owasm_abi::eth::Sink) {
+					let index = match self {
+						#(#variants => #indices,)*
+					};
+					sink.push(index)
+				}
+
+				const IS_FIXED: bool = true;
+
+				const CANONICAL: owasm_abi::eth::Canonical = owasm_abi::eth::Canonical::Uint { bits: 8 };
+			}
+
+			impl owasm_abi::eth::AsLog for #name {
+				fn as_log(&self) -> owasm_abi::types::H256 {
+					let index = match *self {
+						#(#variants => #indices,)*
+					};
+					owasm_abi::eth::AsLog::as_log(&index)
+				}
+			}
+
+			impl owasm_abi::eth::EncodeInto for #name {
+				type Owned = #name;
+
+				fn encode_into(
+					&self,
+					sink: &mut owasm_abi::eth::BufferSink,
+				) -> Result<(), owasm_abi::eth::BufferOverflow> {
+					let index = match *self {
+						#(#variants => #indices,)*
+					};
+					sink.push(&index)
+				}
+			}
+
+			impl owasm_abi::eth::PackedAbiType for #name {
+				fn encode_packed(self, sink: &mut owasm_abi::eth::PackedSink) {
+					let index = match self {
+						#(#variants => #indices,)*
+					};
+					sink.push(index)
+				}
+			}
+
+			impl owasm_abi::eth::PackedMember for #name {}
+		};
+	}
+}
@@ -1,7 +1,5 @@
 use std;
 
-use json::JsonError;
-
 /// The result type for this procedural macro.
 pub type Result<T> = std::result::Result<T, Error>;
 
@@ -17,8 +15,6 @@ pub struct Error {
 /// of this procedural macro.
 #[derive(Debug)]
 pub enum ErrorKind {
-	/// An error that occured upon a JSON operation.
-	JsonError(JsonError),
 	/// When there was an invalid number of arguments passed to `eth_abi`.
 	InvalidNumberOfArguments {
 		/// The number of found arguments.
@@ -31,12 +27,6 @@ pub enum ErrorKind {
 	},
 }
 
-impl From<JsonError> for Error {
-	fn from(json_err: JsonError) -> Self {
-		Error::from_kind(ErrorKind::JsonError(json_err))
-	}
-}
-
 impl Error {
 	/// Create an error from the given kind.
 	///
@@ -70,7 +60,6 @@ impl Error {
 impl std::fmt::Display for Error {
 	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::result::Result<(), std::fmt::Error> {
 		match self.kind() {
-			ErrorKind::JsonError(err) => write!(f, "{}", err),
 			ErrorKind::InvalidNumberOfArguments { found } => write!(
 				f,
 				"found {} arguments passed to eth_abi but expected 1 or 2",
@@ -88,7 +77,6 @@ impl std::fmt::Display for Error {
 impl std::error::Error for Error {
 	fn description(&self) -> &str {
 		match self.kind() {
-            ErrorKind::JsonError(err) => err.description(),
 			ErrorKind::InvalidNumberOfArguments{ .. } => {
 				"encountered an invalid number of arguments passed to eth_abi: expected 1 or 2"
 			},
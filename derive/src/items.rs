@@ -7,9 +7,6 @@ use proc_macro2::{self, Span};
 pub struct Event {
 	/// The name of the event.
 	pub name: syn::Ident,
-	/// The canonalized string representation used by the keccak hash
-	/// in order to retrieve the first 4 bytes required upon calling.
-	pub canonical: String,
 	/// The signature of the event.
 	pub method_sig: syn::MethodSig,
 	/// Indexed parameters.
@@ -23,6 +20,32 @@ pub struct Event {
 	pub data: Vec<(syn::Pat, syn::Type)>,
 }
 
+impl Event {
+	/// Returns the identifier of the struct the event is decoded into,
+	/// i.e. `BazFiredEvent` for the `baz_fired` event.
+	pub fn struct_ident(&self) -> syn::Ident {
+		let name = utils::camel_case(&self.name.to_string()) + "Event";
+		syn::Ident::new(&name, Span::call_site())
+	}
+}
+
+/// Represents a custom error of a smart contract its functions can revert with.
+pub struct CustomError {
+	/// The name of the error.
+	pub name: syn::Ident,
+	/// The parameters of this error.
+	pub arguments: Vec<(syn::Pat, syn::Type)>,
+}
+
+impl CustomError {
+	/// Returns the identifier of the struct the error is represented by,
+	/// i.e. `InsufficientBalance` for the `InsufficientBalance` or
+	/// `insufficient_balance` error.
+	pub fn struct_ident(&self) -> syn::Ident {
+		syn::Ident::new(&utils::camel_case(&self.name.to_string()), Span::call_site())
+	}
+}
+
 /// Represents a function declared in the contracts interface.
 /// 
 /// Since this is basically just the declaration of such as function
@@ -31,16 +54,18 @@ pub struct Event {
 pub struct Signature {
 	/// The name of this signature.
 	pub name: syn::Ident,
-	/// The canonicalized string representation of this signature.
-	pub canonical: String,
 	/// The parameter information of this signature.
 	pub method_sig: syn::MethodSig,
-	/// The function selector hash (4 bytes) of this signature.
-	pub hash: u32,
 	/// The arguments of this signature.
 	pub arguments: Vec<(syn::Pat, syn::Type)>,
 	/// The return type of this signature.
+	///
+	/// # Note
+	///
+	/// For a signature returning `Result<T, E>` these are the types of `T`.
 	pub return_types: Vec<syn::Type>,
+	/// The error type `E` of a signature returning `Result<T, E>`.
+	pub error_type: Option<syn::Type>,
 	/// If this signature is constant.
 	/// 
 	/// # Note
@@ -55,12 +80,40 @@ pub struct Signature {
 	pub is_payable: bool,
 }
 
+impl Signature {
+	/// Returns `true` if this signature returns a tuple.
+	///
+	/// # Note
+	///
+	/// The members of a returned tuple are encoded as multiple return values.
+	pub fn returns_tuple(&self) -> bool {
+		match output_types(&self.method_sig).0 {
+			Some(syn::Type::Tuple(_)) => true,
+			_ => false,
+		}
+	}
+}
+
+/// Returns the type returned by the given signature on success and its error type,
+/// i.e. `T` and `E` for a signature returning `Result<T, E>`.
+fn output_types(method_sig: &syn::MethodSig) -> (Option<syn::Type>, Option<syn::Type>) {
+	match method_sig.decl.output {
+		syn::ReturnType::Type(_, ref ty) => match utils::result_types(ty) {
+			Some((ok_type, error_type)) => (Some(ok_type), Some(error_type)),
+			None => (Some((**ty).clone()), None),
+		},
+		syn::ReturnType::Default => (None, None),
+	}
+}
+
 /// An item within a contract trait.
 pub enum Item {
 	/// An invokable function.
 	Signature(Signature),
 	/// An event.
 	Event(Event),
+	/// A custom error.
+	Error(CustomError),
 	/// Some trait item that is unsupported and unhandled as of now.
 	Other(syn::TraitItem),
 }
@@ -82,6 +135,7 @@ pub struct Interface {
 	/// These are either
 	/// - `Signature`: A function declaration
 	/// - `Event`: An event
+	/// - `Error`: A custom error
 	/// - `Other`: Some unsupported and unhandled trait item
 	items: Vec<Item>,
 }
@@ -92,12 +146,13 @@ impl Item {
 	/// # Note
 	/// 
 	/// Only returns a name if it is a supported kind of item.
-	/// Only `Signature` and `Event` kinds are supported.
+	/// Only `Signature`, `Event` and `Error` kinds are supported.
 	fn name(&self) -> Option<&syn::Ident> {
 		use Item::*;
 		match *self {
 			Signature(ref sig) => Some(&sig.name),
 			Event(ref event) => Some(&event.name),
+			Error(ref error) => Some(&error.name),
 			Other(_) => None,
 		}
 	}
@@ -153,27 +208,18 @@ fn into_signature(
 	-> Signature
 {
 	let arguments: Vec<(syn::Pat, syn::Type)> = utils::iter_signature(&method_sig).collect();
-	let return_types: Vec<syn::Type> = match method_sig.decl.output.clone() {
-		syn::ReturnType::Default => Vec::new(),
-		syn::ReturnType::Type(_, ty) => {
-			match *ty {
-				syn::Type::Tuple(tuple_type) => {
-					tuple_type.elems.into_iter().collect()
-				},
-				ty => vec![ty],
-			}
-		},
+	let (output_type, error_type) = output_types(&method_sig);
+	let return_types: Vec<syn::Type> = match output_type {
+		None => Vec::new(),
+		Some(syn::Type::Tuple(tuple_type)) => tuple_type.elems.into_iter().collect(),
+		Some(ty) => vec![ty],
 	};
-	let canonical = utils::canonicalize_fn(&ident, &method_sig);
-	let hash = utils::function_selector(&canonical);
-
 	Signature {
 		name: ident,
 		arguments: arguments,
 		method_sig: method_sig,
-		canonical: canonical,
-		hash: hash,
 		return_types: return_types,
+		error_type: error_type,
 		is_constant: is_constant,
 		is_payable: is_payable,
 	}
@@ -196,10 +242,8 @@ impl Item {
 		);
 		let (indexed, non_indexed) = utils::iter_signature(&method_sig)
 			.partition(|&(ref pat, _)| quote! { #pat }.to_string().starts_with("indexed_"));
-		let canonical = utils::canonicalize_fn(&method_sig.ident, &method_sig);
 		let event = Event {
 			name: method_sig.ident.clone(),
-			canonical: canonical,
 			indexed: indexed,
 			data: non_indexed,
 			method_sig: method_sig,
@@ -207,6 +251,22 @@ impl Item {
 		Item::Event(event)
 	}
 
+	fn error_from_trait_item(method_sig: syn::MethodSig) -> Self {
+		assert!(
+			method_sig.ident != "constructor",
+			"The constructor can't be an error"
+		);
+		assert!(
+			method_sig.decl.output == syn::ReturnType::Default,
+			format!("Error {} cannot have a return type", method_sig.ident.to_string())
+		);
+		let error = CustomError {
+			name: method_sig.ident.clone(),
+			arguments: utils::iter_signature(&method_sig).collect(),
+		};
+		Item::Error(error)
+	}
+
 	fn signature_from_trait_item(method_trait_item: syn::TraitItemMethod) -> Self {
 		let constant = has_attribute(&method_trait_item.attrs, "constant");
 		let payable = has_attribute(&method_trait_item.attrs, "payable");
@@ -238,6 +298,9 @@ impl Item {
 				if has_attribute(&method_trait_item.attrs, "event") {
 					return Self::event_from_trait_item(method_trait_item.sig)
 				}
+				if has_attribute(&method_trait_item.attrs, "error") {
+					return Self::error_from_trait_item(method_trait_item.sig)
+				}
 				Self::signature_from_trait_item(method_trait_item)
 			},
 			trait_item => Item::Other(trait_item)
@@ -256,29 +319,28 @@ impl quote::ToTokens for Item {
 						name,
 						method_sig,
 						{
-							let keccak = utils::keccak(&event.canonical.as_bytes());
-							let hash_bytes = keccak.as_ref().iter().map(|b| {
-								syn::Lit::Int(syn::LitInt::new(*b as u64, syn::IntSuffix::U8, Span::call_site() ))
-							});
+							let struct_ident = event.struct_ident();
 
 							let indexed_pats = event.indexed.iter()
 								.map(|&(ref pat, _)| pat);
 
-							let data_pats = event.data.iter()
-								.map(|&(ref pat, _)| pat);
+							let data_push = event.data.iter()
+								.map(|&(ref pat, ref ty)| utils::push_arg(pat, ty));
 
-							let data_pats_count_lit = syn::Lit::Int(
-								syn::LitInt::new(event.data.len() as u64, syn::IntSuffix::Usize, Span::call_site()));
+							let data_words = utils::head_words(event.data.iter().map(|&(_, ref ty)| ty));
 
 							quote! {
 								let topics = &[
-									[#(#hash_bytes),*].into(),
+									#struct_ident::SIGNATURE_HASH.into(),
 									#(::owasm_abi::eth::AsLog::as_log(&#indexed_pats)),*
 								];
 
-								let mut sink = ::owasm_abi::eth::Sink::new(#data_pats_count_lit);
-								#(sink.push(#data_pats));*;
-								let payload = sink.finalize_panicking();
+								let mut sink = ::owasm_abi::eth::Sink::new(#data_words);
+								#(#data_push)*
+								let payload = match sink.finalize() {
+									Ok(payload) => payload,
+									Err(err) => panic!("event data encoding failed: {}", err),
+								};
 
 								::owasm_ethereum::log(topics, &payload);
 							}
@@ -296,6 +358,8 @@ impl quote::ToTokens for Item {
 					}
 				).into_token_stream());
 			},
+			// errors are not part of the trait, they are represented by generated structs
+			Item::Error(_) => {},
 			Item::Other(ref item) => {
 				tokens.append_all(&[item]);
 			}
@@ -1,230 +1,89 @@
-//! JSON generation
+//! JSON ABI generation
 
 use {items, utils};
-use serde_json;
+use proc_macro2;
+use syn;
 
-use std::{io};
-use std;
-
-/// The result type for JSON errors.
-pub type JsonResult<T> = std::result::Result<T, JsonError>;
-
-/// Errors that may occur during JSON operations.
-#[derive(Debug)]
-pub enum JsonError {
-	FailedToCreateDirectory(io::Error),
-	FailedToCreateJsonFile(io::Error),
-	FailedToWriteJsonAbiFile(serde_json::Error),
-}
-
-impl JsonError {
-	/// Returns a JSON error indicating that the creation of the
-	/// directory that will contain the JSON file failed.
-	pub fn failed_to_create_dir(err: io::Error) -> Self {
-		JsonError::FailedToCreateDirectory(err)
+/// Returns the constant expression of the JSON ABI entries of the interface.
+///
+/// Parameter types are given by their `AbiType::CANONICAL`, so that
+/// `owasm_abi::eth::json_abi` lists structs as tuples with their components
+/// and enums as `uint8`, which the macro can't tell from their names.
+pub fn json_entries(intf: &items::Interface) -> proc_macro2::TokenStream {
+	let mut entries = Vec::new();
+	for item in intf.items() {
+		match *item {
+			items::Item::Event(ref event) => entries.push(event_entry(event)),
+			items::Item::Signature(ref signature) => entries.push(function_entry(signature)),
+			items::Item::Error(ref error) => entries.push(error_entry(error)),
+			_ => {}
+		}
 	}
 
-	/// Returns a JSON error indicating that the creation of the JSON
-	/// abi file failed.
-	pub fn failed_to_create_json_file(err: io::Error) -> Self {
-		JsonError::FailedToCreateJsonFile(err)
+	if let Some(constructor) = intf.constructor() {
+		let inputs = params(&constructor.arguments, false);
+		entries.push(quote! {
+			owasm_abi::eth::JsonEntry::Constructor { inputs: &[#(#inputs),*] }
+		});
 	}
 
-	/// Returns a JSON error indicating that the writing of the JSON
-	/// abi file failed.
-	pub fn failed_to_write_json_abi_file(err: serde_json::Error) -> Self {
-		JsonError::FailedToWriteJsonAbiFile(err)
-	}
+	quote! { &[#(#entries),*] }
 }
 
-impl std::fmt::Display for JsonError {
-	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::result::Result<(), std::fmt::Error> {
-		match self {
-			JsonError::FailedToCreateDirectory(err) => {
-				write!(f, "failed to create directory for JSON abi file: {:?}", err)
-			}
-			JsonError::FailedToCreateJsonFile(err) => {
-				write!(f, "failed to create JSON abi file: {:?}", err)
-			}
-			JsonError::FailedToWriteJsonAbiFile(err) => {
-				write!(f, "failed to write JSON abi file: {:?}", err)
-			}
+fn param(name: &str, ty: &syn::Type, indexed: bool) -> proc_macro2::TokenStream {
+	let canonical = utils::canonical(ty);
+	quote! {
+		owasm_abi::eth::JsonParam {
+			name: #name,
+			canonical: #canonical,
+			indexed: #indexed,
 		}
 	}
 }
 
-impl std::error::Error for JsonError {
-	fn description(&self) -> &str {
-		match self {
-			JsonError::FailedToCreateDirectory(_) => {
-				"failed to create directory for the JSON abi file"
-			}
-			JsonError::FailedToCreateJsonFile(_) => "failed to create JSON abi file",
-			JsonError::FailedToWriteJsonAbiFile(_) => "failed to write JSON abi file",
-		}
-	}
+fn params(arguments: &[(syn::Pat, syn::Type)], indexed: bool) -> Vec<proc_macro2::TokenStream> {
+	arguments
+		.iter()
+		.map(|&(ref pat, ref ty)| param(&quote! { #pat }.to_string(), ty, indexed))
+		.collect()
+}
 
-	fn cause(&self) -> Option<&std::error::Error> {
-		match self {
-			JsonError::FailedToCreateDirectory(err) => Some(err),
-			JsonError::FailedToCreateJsonFile(err) => Some(err),
-			JsonError::FailedToWriteJsonAbiFile(err) => Some(err),
+fn event_entry(item: &items::Event) -> proc_macro2::TokenStream {
+	let name = item.name.to_string();
+	let inputs = params(&item.indexed, true).into_iter().chain(params(&item.data, false));
+	quote! {
+		owasm_abi::eth::JsonEntry::Event {
+			name: #name,
+			inputs: &[#(#inputs),*],
 		}
 	}
 }
 
-/// Writes generated abi JSON file to destination in default target directory.
-///
-/// # Note
-///
-/// The generated JSON information may be used by offline tools around WebJS for example.
-pub fn write_json_abi(intf: &items::Interface) -> JsonResult<()> {
-	use std::{env, fs, path};
-
-	let target = {
-		let mut target =
-			path::PathBuf::from(env::var("CARGO_TARGET_DIR").unwrap_or(".".to_owned()));
-		target.push("target");
-		target.push("json");
-		fs::create_dir_all(&target).map_err(|err| JsonError::failed_to_create_dir(err))?;
-		target.push(&format!("{}.json", intf.name()));
-		target
-	};
-
-	let mut f =
-		fs::File::create(target).map_err(|err| JsonError::failed_to_create_json_file(err))?;
-
-	let abi: Abi = intf.into();
-
-	serde_json::to_writer_pretty(&mut f, &abi)
-		.map_err(|err| JsonError::failed_to_write_json_abi_file(err))?;
-
-	Ok(())
-}
-
-#[derive(Serialize, Debug)]
-pub struct FunctionEntry {
-    pub name: String,
-    #[serde(rename = "inputs")]
-    pub arguments: Vec<Argument>,
-    pub outputs: Vec<Argument>,
-    pub constant: bool,
-}
-
-#[derive(Serialize, Debug)]
-pub struct Argument {
-    pub name: String,
-    #[serde(rename = "type")]
-    pub type_: String,
-}
-
-#[derive(Serialize, Debug)]
-pub struct ConstructorEntry {
-    #[serde(rename = "inputs")]
-    pub arguments: Vec<Argument>,
-}
-
-#[derive(Serialize, Debug)]
-#[serde(tag = "type")]
-pub enum AbiEntry {
-    #[serde(rename = "event")]
-    Event(EventEntry),
-    #[serde(rename = "function")]
-    Function(FunctionEntry),
-    #[serde(rename = "constructor")]
-    Constructor(ConstructorEntry),
-}
-
-#[derive(Serialize, Debug)]
-pub struct EventInput {
-    pub name: String,
-    #[serde(rename = "type")]
-    pub type_: String,
-    pub indexed: bool,
-}
-
-#[derive(Serialize, Debug)]
-pub struct EventEntry {
-    pub name: String,
-    pub inputs: Vec<EventInput>,
-}
-
-#[derive(Serialize, Debug)]
-pub struct Abi(pub Vec<AbiEntry>);
-
-impl<'a> From<&'a items::Interface> for Abi {
-    fn from(intf: &items::Interface) -> Self {
-        let mut result = Vec::new();
-        for item in intf.items() {
-            match *item {
-                items::Item::Event(ref event) => result.push(AbiEntry::Event(event.into())),
-                items::Item::Signature(ref signature) => result.push(AbiEntry::Function(signature.into())),
-                _ => {}
-            }
-        }
-
-        if let Some(constructor) = intf.constructor() {
-            result.push(AbiEntry::Constructor(FunctionEntry::from(constructor).into()));
-        }
-
-        Abi(result)
-    }
-}
-
-impl<'a> From<&'a items::Event> for EventEntry {
-    fn from(item: &items::Event) -> Self {
-        EventEntry {
-            name: item.name.to_string(),
-            inputs: item.indexed
-                .iter()
-                .map(|&(ref pat, ref ty)|
-                    EventInput {
-                        name: quote! { #pat }.to_string(),
-                        type_: utils::canonicalize_type(ty),
-                        indexed: true,
-                    }
-                )
-                .chain(
-                    item.data
-                        .iter()
-                        .map(|&(ref pat, ref ty)|
-                            EventInput {
-                                name: quote! { #pat }.to_string(),
-                                type_: utils::canonicalize_type(ty),
-                                indexed: false,
-                            }
-                        )
-                    )
-                .collect(),
-        }
-    }
-}
-
-impl<'a> From<&'a items::Signature> for FunctionEntry {
-    fn from(item: &items::Signature) -> Self {
-        FunctionEntry {
-            name: item.name.to_string(),
-            arguments: item.arguments
-                .iter()
-                .map(|&(ref pat, ref ty)|
-                    Argument {
-                        name: quote! { #pat }.to_string(),
-                        type_: utils::canonicalize_type(ty),
-                    }
-                )
-                .collect(),
-            outputs: item.return_types
-                .iter()
-                .enumerate()
-                .map(|(idx, ty)| Argument { name: format!("returnValue{}", idx), type_: utils::canonicalize_type(ty) })
-                .collect(),
-            constant: item.is_constant,
-        }
-    }
+fn error_entry(item: &items::CustomError) -> proc_macro2::TokenStream {
+	let name = item.name.to_string();
+	let inputs = params(&item.arguments, false);
+	quote! {
+		owasm_abi::eth::JsonEntry::Error {
+			name: #name,
+			inputs: &[#(#inputs),*],
+		}
+	}
 }
 
-impl From<FunctionEntry> for ConstructorEntry {
-    fn from(func: FunctionEntry) -> Self {
-        ConstructorEntry { arguments: func.arguments }
-    }
+fn function_entry(item: &items::Signature) -> proc_macro2::TokenStream {
+	let name = item.name.to_string();
+	let inputs = params(&item.arguments, false);
+	let outputs = item.return_types
+		.iter()
+		.enumerate()
+		.map(|(idx, ty)| param(&format!("returnValue{}", idx), ty, false));
+	let constant = item.is_constant;
+	quote! {
+		owasm_abi::eth::JsonEntry::Function {
+			name: #name,
+			inputs: &[#(#inputs),*],
+			outputs: &[#(#outputs),*],
+			constant: #constant,
+		}
+	}
 }
@@ -1,5 +1,5 @@
 //! Ethereum (Solidity) derivation for rust contracts (compiled to wasm or otherwise)
-#![recursion_limit = "128"]
+#![recursion_limit = "256"]
 #![deny(unused)]
 
 extern crate proc_macro;
@@ -11,14 +11,7 @@ extern crate syn;
 #[macro_use]
 extern crate quote;
 
-extern crate byteorder;
-extern crate parity_hash;
-extern crate serde_json;
-extern crate tiny_keccak;
-
-#[macro_use]
-extern crate serde_derive;
-
+mod abi_type;
 mod error;
 mod items;
 mod json;
@@ -26,7 +19,6 @@ mod utils;
 
 use error::{Error, Result};
 use items::Item;
-use json::write_json_abi;
 use proc_macro2::Span;
 
 /// Arguments given to the `eth_abi` attribute macro.
@@ -87,12 +79,38 @@ impl Args {
 /// ## Endpoint
 ///
 /// Converts ABI encoded payload into a called function with its parameters.
+/// Calls which fail, e.g. because their arguments can't be decoded, make
+/// `try_dispatch` return the payload to revert with, while `dispatch` fails
+/// the call with it through `Revert::abort`.
 ///
 /// ## Client
 ///
 /// Opposite of an endpoint that allows users (clients) to build up queries
 /// in the form of a payload to functions of a contract by a generated interface.
 ///
+/// ## Events
+///
+/// Every `#[event]` gets a struct named after it, like `BazFiredEvent` for
+/// `baz_fired`, with the `SIGNATURE_HASH` constant and a `decode` constructor
+/// which turns the topics and data of a log back into the event parameters.
+///
+/// ## Errors
+///
+/// Every `#[error]`, like `#[error] fn InsufficientBalance(&mut self, needed: U256);`,
+/// is removed from the trait and becomes a struct of the same name with the
+/// `SELECTOR` constant and a `decode` constructor for its revert payload. All errors
+/// of the interface are collected into an enum named after it, like `ContractError`
+/// for the `Contract` trait. Functions returning `Result<T, E>` return `T` to the
+/// caller and revert with the payload of `E` on failure, where `E` is any
+/// `owasm_abi::eth::ContractError`, i.e. one of these structs or the enum.
+/// The client returns `Err(E)` decoded from the revert payload of failed calls.
+///
+/// ## JSON ABI
+///
+/// The endpoint has a `JSON_ABI` constant listing the functions, events, errors and
+/// the constructor of the interface, from which `owasm_abi::eth::json_abi` gives the
+/// JSON ABI read by the Ethereum tooling, e.g. `json_abi(Endpoint::<Instance>::JSON_ABI)`.
+///
 /// # Example: Using just one argument
 ///
 /// ```
@@ -136,8 +154,6 @@ fn impl_eth_abi(args: syn::AttributeArgs, input: syn::Item) -> Result<proc_macro
     let args = Args::from_attribute_args(args)?;
     let intf = items::Interface::from_item(input);
 
-    write_json_abi(&intf)?;
-
     match args.client_name() {
         None => generate_eth_endpoint_wrapper(&intf, args.endpoint_name()),
         Some(client_name) => {
@@ -161,6 +177,13 @@ fn generate_eth_endpoint_wrapper(
 
     let endpoint_toks = generate_eth_endpoint(endpoint_name, intf);
     let endpoint_ident = syn::Ident::new(endpoint_name, Span::call_site());
+    let (type_idents, type_toks): (Vec<_>, Vec<_>) = generate_eth_events(intf)
+        .into_iter()
+        .chain(generate_eth_errors(intf))
+        .unzip();
+    let type_uses = type_idents
+        .iter()
+        .map(|ident| quote! { pub use self::#mod_name_ident::#ident; });
 
     Ok(quote! {
         #intf
@@ -169,10 +192,14 @@ fn generate_eth_endpoint_wrapper(
             extern crate owasm_ethereum;
             extern crate owasm_abi;
             use owasm_abi::types::*;
+            #[allow(unused_imports)]
+            use super::*;
             use super::#name_ident_use;
             #endpoint_toks
+            #(#type_toks)*
         }
         pub use self::#mod_name_ident::#endpoint_ident;
+        #(#type_uses)*
     })
 }
 
@@ -194,6 +221,13 @@ fn generate_eth_endpoint_and_client_wrapper(
     let client_toks = generate_eth_client(client_name, &intf);
     let endpoint_name_ident = syn::Ident::new(endpoint_name, Span::call_site());
     let client_name_ident = syn::Ident::new(&client_name, Span::call_site());
+    let (type_idents, type_toks): (Vec<_>, Vec<_>) = generate_eth_events(intf)
+        .into_iter()
+        .chain(generate_eth_errors(intf))
+        .unzip();
+    let type_uses = type_idents
+        .iter()
+        .map(|ident| quote! { pub use self::#mod_name_ident::#ident; });
 
     Ok(quote! {
         #intf
@@ -202,12 +236,16 @@ fn generate_eth_endpoint_and_client_wrapper(
             extern crate owasm_ethereum;
             extern crate owasm_abi;
             use owasm_abi::types::*;
+            #[allow(unused_imports)]
+            use super::*;
             use super::#name_ident_use;
             #endpoint_toks
             #client_toks
+            #(#type_toks)*
         }
         pub use self::#mod_name_ident::#endpoint_name_ident;
         pub use self::#mod_name_ident::#client_name_ident;
+        #(#type_uses)*
     })
 }
 
@@ -227,25 +265,46 @@ fn generate_eth_client(client_name: &str, intf: &items::Interface) -> proc_macro
     let calls: Vec<proc_macro2::TokenStream> = intf.items().iter().filter_map(|item| {
 		match *item {
 			Item::Signature(ref signature)  => {
-				let hash_literal = syn::Lit::Int(
-					syn::LitInt::new(signature.hash as u64, syn::IntSuffix::U32, Span::call_site()));
+				let canonical = utils::canonical_signature(
+					&signature.name,
+					signature.arguments.iter().map(|&(_, ref ty)| ty),
+				);
 				let argument_push: Vec<proc_macro2::TokenStream> = utils::iter_signature(&signature.method_sig)
-					.map(|(pat, _)| quote! { sink.push(#pat); })
+					.map(|(pat, ty)| utils::push_arg(&pat, &ty))
 					.collect();
-				let argument_count_literal = syn::Lit::Int(
-					syn::LitInt::new(argument_push.len() as u64, syn::IntSuffix::Usize, Span::call_site()));
+				let argument_words = utils::head_words(signature.arguments.iter().map(|&(_, ref ty)| ty));
 
 				let result_instance = match signature.method_sig.decl.output {
 					syn::ReturnType::Default => quote!{
 						let mut result = Vec::new();
 					},
-					syn::ReturnType::Type(_, _) => quote!{
-						let mut result = [0u8; 32];
+					syn::ReturnType::Type(_, _) => {
+						let return_words = utils::head_words(&signature.return_types);
+						let return_types = &signature.return_types;
+						let can_revert = signature.error_type.is_some();
+						// the host doesn't tell the size of the output, so dynamic return
+						// values and revert payloads are read into a buffer of `output_limit` bytes
+						quote!{
+							let output_len = if !#can_revert #(&& <#return_types as owasm_abi::eth::AbiType>::IS_FIXED)* {
+								32 * (#return_words)
+							} else {
+								self.output_limit
+							};
+							let mut result = Vec::new();
+							result.resize(output_len, 0u8);
+						}
 					},
 				};
 
+				let return_types = &signature.return_types;
 				let result_pop = match signature.method_sig.decl.output {
 					syn::ReturnType::Default => None,
+					syn::ReturnType::Type(_, _) if signature.returns_tuple() => Some(
+						quote!{
+							let mut stream = owasm_abi::eth::Stream::new(&result);
+							(#(stream.pop::<#return_types>().expect("failed decode call output"),)*)
+						}
+					),
 					syn::ReturnType::Type(_, _) => Some(
 						quote!{
 							let mut stream = owasm_abi::eth::Stream::new(&result);
@@ -253,6 +312,23 @@ fn generate_eth_client(client_name: &str, intf: &items::Interface) -> proc_macro
 						}
 					),
 				};
+				let result_pop = match signature.error_type {
+					Some(_) => result_pop.map(|result_pop| quote!{ Ok({ #result_pop }) }),
+					None => result_pop,
+				};
+				let call_result = match signature.error_type {
+					Some(ref error_type) => quote!{
+						if call_result.is_err() {
+							return Err(
+								<#error_type as owasm_abi::eth::ContractError>::decode(&result)
+									.expect("failed decode revert payload")
+							);
+						}
+					},
+					None => quote!{
+						call_result.expect("Call failed; todo: allow handling inside contracts");
+					},
+				};
 
 				Some(utils::produce_signature(
 					&signature.name,
@@ -260,21 +336,22 @@ fn generate_eth_client(client_name: &str, intf: &items::Interface) -> proc_macro
 					quote!{
 						#![allow(unused_mut)]
 						#![allow(unused_variables)]
-						let mut payload = Vec::with_capacity(4 + #argument_count_literal * 32);
-						payload.push((#hash_literal >> 24) as u8);
-						payload.push((#hash_literal >> 16) as u8);
-						payload.push((#hash_literal >> 8) as u8);
-						payload.push(#hash_literal as u8);
-
-						let mut sink = owasm_abi::eth::Sink::new(#argument_count_literal);
+						const SELECTOR: u32 = #canonical.selector();
+						let mut payload = Vec::with_capacity(4 + (#argument_words) * 32);
+						payload.push((SELECTOR >> 24) as u8);
+						payload.push((SELECTOR >> 16) as u8);
+						payload.push((SELECTOR >> 8) as u8);
+						payload.push(SELECTOR as u8);
+
+						let mut sink = owasm_abi::eth::Sink::new(#argument_words);
 						#(#argument_push)*
 
 						sink.drain_to(&mut payload);
 
 						#result_instance
 
-						owasm_ethereum::call(self.gas.unwrap_or(200000), &self.address, self.value.clone().unwrap_or(U256::zero()), &payload, &mut result[..])
-							.expect("Call failed; todo: allow handling inside contracts");
+						let call_result = owasm_ethereum::call(self.gas.unwrap_or(200000), &self.address, self.value.clone().unwrap_or(U256::zero()), &payload, &mut result[..]);
+						#call_result
 
 						#result_pop
 					}
@@ -302,6 +379,7 @@ fn generate_eth_client(client_name: &str, intf: &items::Interface) -> proc_macro
             gas: Option<u64>,
             address: Address,
             value: Option<U256>,
+            output_limit: usize,
         }
 
         impl #client_ident {
@@ -310,9 +388,17 @@ fn generate_eth_client(client_name: &str, intf: &items::Interface) -> proc_macro
                     gas: None,
                     address: address,
                     value: None,
+                    output_limit: 4096,
                 }
             }
 
+            /// Sets the size in bytes of the buffer dynamic return values are read into,
+            /// 4096 by default. Outputs which don't fit into it fail to decode.
+            pub fn output_limit(mut self, len: usize) -> Self {
+                self.output_limit = len;
+                self
+            }
+
             pub fn gas(mut self, gas: u64) -> Self {
                 self.gas = Some(gas);
                 self
@@ -331,6 +417,238 @@ fn generate_eth_client(client_name: &str, intf: &items::Interface) -> proc_macro
     }
 }
 
+/// Generates a struct for every event of the interface, to decode the event from its log.
+fn generate_eth_events(intf: &items::Interface) -> Vec<(syn::Ident, proc_macro2::TokenStream)> {
+    intf.items()
+        .iter()
+        .filter_map(|item| match *item {
+            Item::Event(ref event) => Some((event.struct_ident(), generate_eth_event(event))),
+            _ => None,
+        })
+        .collect()
+}
+
+fn generate_eth_event(event: &items::Event) -> proc_macro2::TokenStream {
+    let struct_ident = event.struct_ident();
+    let param_types: Vec<syn::Type> = utils::iter_signature(&event.method_sig)
+        .map(|(_, ty)| ty)
+        .collect();
+    let canonical = utils::canonical_signature(&event.name, &param_types);
+    let topic_count = 1 + event.indexed.len();
+
+    let mut fields = Vec::new();
+    let mut types = Vec::new();
+    let mut values = Vec::new();
+    let mut topic = 0usize;
+    for (pat, ty) in utils::iter_signature(&event.method_sig) {
+        let name = quote! { #pat }.to_string();
+        let is_indexed = name.starts_with("indexed_");
+        fields.push(syn::Ident::new(name.trim_start_matches("indexed_"), Span::call_site()));
+        if !is_indexed {
+            let ty = utils::owned_type(&ty);
+            values.push(quote! { stream.pop::<#ty>()? });
+            types.push(ty);
+            continue;
+        }
+
+        topic += 1;
+        if utils::is_value_type(&ty) {
+            values.push(quote! { owasm_abi::eth::Stream::new(topics[#topic].as_ref()).pop::<#ty>()? });
+            types.push(ty);
+        } else {
+            // only the hash of the value is logged
+            values.push(quote! { topics[#topic] });
+            types.push(parse_quote! { H256 });
+        }
+    }
+
+    let doc = format!("Event `{}` decoded from its log.", event.name);
+    let (field_decls, field_inits) = (&fields, &fields);
+    quote! {
+        #[doc = #doc]
+        pub struct #struct_ident {
+            #(pub #field_decls: #types,)*
+        }
+
+        impl #struct_ident {
+            /// Keccak hash of the event signature, the first topic of its logs.
+            pub const SIGNATURE_HASH: [u8; 32] = #canonical.hash();
+
+            /// Decodes the event from the topics and data of its log.
+            ///
+            /// Indexed `bytes`, `string`, arrays and tuples are decoded as their hash.
+            pub fn decode(topics: &[H256], data: &[u8]) -> Result<Self, owasm_abi::eth::Error> {
+                if topics.len() != #topic_count || topics[0].as_ref() != &Self::SIGNATURE_HASH[..] {
+                    return Err(owasm_abi::eth::Error::InvalidTopic);
+                }
+                #[allow(unused_mut, unused_variables)]
+                let mut stream = owasm_abi::eth::Stream::new(data);
+                Ok(#struct_ident {
+                    #(#field_inits: #values,)*
+                })
+            }
+        }
+    }
+}
+
+/// Generates a struct for every error of the interface, and an enum of all of them
+/// named after the interface.
+fn generate_eth_errors(intf: &items::Interface) -> Vec<(syn::Ident, proc_macro2::TokenStream)> {
+    let errors: Vec<&items::CustomError> = intf
+        .items()
+        .iter()
+        .filter_map(|item| match *item {
+            Item::Error(ref error) => Some(error),
+            _ => None,
+        })
+        .collect();
+    if errors.is_empty() {
+        return Vec::new();
+    }
+
+    let enum_ident = syn::Ident::new(&format!("{}Error", intf.name()), Span::call_site());
+    let variants: Vec<syn::Ident> = errors.iter().map(|error| error.struct_ident()).collect();
+    let decode_arms = variants.iter().map(|variant| {
+        quote! {
+            if selector == #variant::SELECTOR {
+                return #variant::decode(payload).map(#enum_ident::#variant);
+            }
+        }
+    });
+    let encode_arms = variants.iter().map(|variant| {
+        quote! {
+            #enum_ident::#variant(error) => owasm_abi::eth::ContractError::encode(error),
+        }
+    });
+    let from_impls = variants.iter().map(|variant| {
+        quote! {
+            impl From<#variant> for #enum_ident {
+                fn from(error: #variant) -> Self {
+                    #enum_ident::#variant(error)
+                }
+            }
+        }
+    });
+
+    let doc = format!("Errors of the `{}` interface.", intf.name());
+    let (variant_decls, variant_types) = (&variants, &variants);
+    let enum_toks = quote! {
+        #[doc = #doc]
+        pub enum #enum_ident {
+            #(#variant_decls(#variant_types),)*
+        }
+
+        impl #enum_ident {
+            /// Decodes any of the errors from its revert payload.
+            pub fn decode(payload: &[u8]) -> Result<Self, owasm_abi::eth::Error> {
+                if payload.len() < 4 {
+                    return Err(owasm_abi::eth::Error::UnknownSelector);
+                }
+                let selector = ((payload[0] as u32) << 24)
+                    + ((payload[1] as u32) << 16)
+                    + ((payload[2] as u32) << 8)
+                    + (payload[3] as u32);
+                #(#decode_arms)*
+                Err(owasm_abi::eth::Error::UnknownSelector)
+            }
+        }
+
+        impl owasm_abi::eth::ContractError for #enum_ident {
+            fn encode(self) -> Vec<u8> {
+                match self {
+                    #(#encode_arms)*
+                }
+            }
+
+            fn decode(payload: &[u8]) -> Result<Self, owasm_abi::eth::Error> {
+                #enum_ident::decode(payload)
+            }
+        }
+
+        #(#from_impls)*
+    };
+
+    errors
+        .into_iter()
+        .map(|error| (error.struct_ident(), generate_eth_error(error)))
+        .chain(Some((enum_ident, enum_toks)))
+        .collect()
+}
+
+fn generate_eth_error(error: &items::CustomError) -> proc_macro2::TokenStream {
+    let struct_ident = error.struct_ident();
+    let canonical = utils::canonical_signature(
+        &error.name,
+        error.arguments.iter().map(|&(_, ref ty)| ty),
+    );
+    let fields: Vec<syn::Ident> = error
+        .arguments
+        .iter()
+        .map(|&(ref pat, _)| syn::Ident::new(&quote! { #pat }.to_string(), Span::call_site()))
+        .collect();
+    let types: Vec<syn::Type> = error
+        .arguments
+        .iter()
+        .map(|&(_, ref ty)| utils::owned_type(ty))
+        .collect();
+    let words = utils::head_words(&types);
+
+    let doc = format!("Error `{}` a function can revert with.", error.name);
+    let (field_decls, field_inits, field_pushes) = (&fields, &fields, &fields);
+    let (decl_types, pop_types) = (&types, &types);
+    quote! {
+        #[doc = #doc]
+        pub struct #struct_ident {
+            #(pub #field_decls: #decl_types,)*
+        }
+
+        impl #struct_ident {
+            /// Selector of the error, the first 4 bytes of its revert payload.
+            pub const SELECTOR: u32 = #canonical.selector();
+
+            /// Decodes the error from its revert payload.
+            pub fn decode(payload: &[u8]) -> Result<Self, owasm_abi::eth::Error> {
+                let selector = [
+                    (Self::SELECTOR >> 24) as u8,
+                    (Self::SELECTOR >> 16) as u8,
+                    (Self::SELECTOR >> 8) as u8,
+                    Self::SELECTOR as u8,
+                ];
+                if payload.len() < 4 || payload[..4] != selector {
+                    return Err(owasm_abi::eth::Error::UnknownSelector);
+                }
+                #[allow(unused_mut)]
+                let mut stream = owasm_abi::eth::Stream::new(&payload[4..]);
+                let error = #struct_ident {
+                    #(#field_inits: stream.pop::<#pop_types>()?,)*
+                };
+                stream.finish()?;
+                Ok(error)
+            }
+        }
+
+        impl owasm_abi::eth::ContractError for #struct_ident {
+            fn encode(self) -> Vec<u8> {
+                let mut payload = Vec::with_capacity(4 + (#words) * 32);
+                payload.push((Self::SELECTOR >> 24) as u8);
+                payload.push((Self::SELECTOR >> 16) as u8);
+                payload.push((Self::SELECTOR >> 8) as u8);
+                payload.push(Self::SELECTOR as u8);
+
+                #[allow(unused_mut)]
+                let mut sink = owasm_abi::eth::Sink::new(#words);
+                #(sink.push(self.#field_pushes);)*
+                sink.drain_to(&mut payload);
+                payload
+            }
+
+            fn decode(payload: &[u8]) -> Result<Self, owasm_abi::eth::Error> {
+                #struct_ident::decode(payload)
+            }
+        }
+    }
+}
+
 fn generate_eth_endpoint(endpoint_name: &str, intf: &items::Interface) -> proc_macro2::TokenStream {
     fn check_value_if_payable_toks(is_payable: bool) -> proc_macro2::TokenStream {
         if is_payable {
@@ -338,79 +656,130 @@ fn generate_eth_endpoint(endpoint_name: &str, intf: &items::Interface) -> proc_m
         }
         quote! {
             if owasm_ethereum::value() > 0.into() {
-                panic!("Unable to accept value in non-payable constructor call");
+                return Err(owasm_abi::eth::Revert::error(
+                    "Unable to accept value in non-payable constructor call"
+                ));
             }
         }
     }
 
     let ctor_branch = intf.constructor().map(|signature| {
-        let arg_types = signature
+        let arg_pops = signature
             .arguments
             .iter()
-            .map(|&(_, ref ty)| quote! { #ty });
+            .enumerate()
+            .map(|(index, &(_, ref ty))| utils::pop_arg(index, ty));
         let check_value_if_payable = check_value_if_payable_toks(signature.is_payable);
         quote! {
             #check_value_if_payable
             let mut stream = owasm_abi::eth::Stream::new(payload);
             self.inner.constructor(
-                #(stream.pop::<#arg_types>().expect("argument decoding failed")),*
+                #(#arg_pops),*
             );
         }
     });
 
-    let branches: Vec<proc_macro2::TokenStream> = intf
+    let signatures: Vec<&items::Signature> = intf
         .items()
         .iter()
         .filter_map(|item| match *item {
-            Item::Signature(ref signature) => {
-                let hash_literal = syn::Lit::Int(syn::LitInt::new(
-                    signature.hash as u64,
-                    syn::IntSuffix::U32,
-                    Span::call_site(),
-                ));
-                let ident = &signature.name;
-                let arg_types = signature
-                    .arguments
-                    .iter()
-                    .map(|&(_, ref ty)| quote! { #ty });
-                let check_value_if_payable = check_value_if_payable_toks(signature.is_payable);
-                if !signature.return_types.is_empty() {
-                    let return_count_literal = syn::Lit::Int(syn::LitInt::new(
-                        signature.return_types.len() as u64,
-                        syn::IntSuffix::Usize,
-                        Span::call_site(),
-                    ));
-                    Some(quote! {
-                        #hash_literal => {
-                            #check_value_if_payable
-                            let mut stream = owasm_abi::eth::Stream::new(method_payload);
-                            let result = inner.#ident(
-                                #(stream.pop::<#arg_types>().expect("argument decoding failed")),*
-                            );
-                            let mut sink = owasm_abi::eth::Sink::new(#return_count_literal);
-                            sink.push(result);
-                            sink.finalize_panicking()
-                        }
-                    })
+            Item::Signature(ref signature) => Some(signature),
+            _ => None,
+        })
+        .collect();
+    // selectors are computed in constants from the canonical types of the arguments
+    let selector_idents: &Vec<syn::Ident> = &(0..signatures.len())
+        .map(|idx| syn::Ident::new(&format!("SELECTOR_{}", idx), Span::call_site()))
+        .collect();
+    let selectors: Vec<proc_macro2::TokenStream> = signatures
+        .iter()
+        .map(|signature| {
+            utils::canonical_signature(
+                &signature.name,
+                signature.arguments.iter().map(|&(_, ref ty)| ty),
+            )
+        })
+        .collect();
+
+    let branches: Vec<proc_macro2::TokenStream> = signatures
+        .iter()
+        .zip(selector_idents)
+        .map(|(signature, selector)| {
+            let ident = &signature.name;
+            let arg_pops = signature
+                .arguments
+                .iter()
+                .enumerate()
+                .map(|(index, &(_, ref ty))| utils::pop_arg(index, ty));
+            let check_value_if_payable = check_value_if_payable_toks(signature.is_payable);
+            let revert_error = quote! {
+                return Err(owasm_abi::eth::Revert::from(err))
+            };
+            if !signature.return_types.is_empty() {
+                let return_words = utils::head_words(&signature.return_types);
+                let result_pats: &Vec<syn::Ident> = &(0..signature.return_types.len())
+                    .map(|idx| syn::Ident::new(&format!("result{}", idx), Span::call_site()))
+                    .collect();
+                let result_bind = if signature.returns_tuple() {
+                    quote! { let (#(#result_pats,)*) = result; }
                 } else {
-                    Some(quote! {
-                        #hash_literal => {
-                            #check_value_if_payable
-                            let mut stream = owasm_abi::eth::Stream::new(method_payload);
-                            inner.#ident(
-                                #(stream.pop::<#arg_types>().expect("argument decoding failed")),*
-                            );
-                            Vec::new()
+                    quote! { let #(#result_pats)* = result; }
+                };
+                let unwrap_result = signature.error_type.as_ref().map(|_| {
+                    quote! {
+                        let result = match result {
+                            Ok(result) => result,
+                            Err(err) => #revert_error,
+                        };
+                    }
+                });
+                quote! {
+                    #selector => {
+                        #check_value_if_payable
+                        let mut stream = owasm_abi::eth::Stream::new(method_payload);
+                        let result = inner.#ident(
+                            #(#arg_pops),*
+                        );
+                        #unwrap_result
+                        #result_bind
+                        let mut sink = owasm_abi::eth::Sink::new(#return_words);
+                        #(sink.push(#result_pats);)*
+                        sink.finalize().map_err(|err| owasm_abi::eth::Revert::error(
+                            &format_args!("return value encoding failed: {}", err)
+                        ))?
+                    }
+                }
+            } else if signature.error_type.is_some() {
+                quote! {
+                    #selector => {
+                        #check_value_if_payable
+                        let mut stream = owasm_abi::eth::Stream::new(method_payload);
+                        if let Err(err) = inner.#ident(
+                            #(#arg_pops),*
+                        ) {
+                            #revert_error
                         }
-                    })
+                        Vec::new()
+                    }
+                }
+            } else {
+                quote! {
+                    #selector => {
+                        #check_value_if_payable
+                        let mut stream = owasm_abi::eth::Stream::new(method_payload);
+                        inner.#ident(
+                            #(#arg_pops),*
+                        );
+                        Vec::new()
+                    }
                 }
             }
-            _ => None,
         })
         .collect();
 
     let endpoint_ident = syn::Ident::new(endpoint_name, Span::call_site());
     let name_ident = syn::Ident::new(&intf.name(), Span::call_site());
+    let json_entries = json::json_entries(intf);
 
     quote! {
         pub struct #endpoint_ident<T: #name_ident> {
@@ -435,15 +804,18 @@ fn generate_eth_endpoint(endpoint_name: &str, intf: &items::Interface) -> proc_m
             pub fn instance(&self) -> &T {
                 &self.inner
             }
+
+            /// Entries of the JSON ABI of the interface, see `owasm_abi::eth::json_abi`
+            pub const JSON_ABI: &'static [owasm_abi::eth::JsonEntry] = #json_entries;
         }
 
         impl<T: #name_ident> owasm_abi::eth::EndpointInterface for #endpoint_ident<T> {
             #[allow(unused_mut)]
             #[allow(unused_variables)]
-            fn dispatch(&mut self, payload: &[u8]) -> Vec<u8> {
+            fn try_dispatch(&mut self, payload: &[u8]) -> Result<Vec<u8>, owasm_abi::eth::Revert> {
                 let inner = &mut self.inner;
                 if payload.len() < 4 {
-                    panic!("Invalid abi invoke");
+                    return Err(owasm_abi::eth::Revert::error("Invalid abi invoke"));
                 }
                 let method_id = ((payload[0] as u32) << 24)
                     + ((payload[1] as u32) << 16)
@@ -452,21 +824,56 @@ fn generate_eth_endpoint(endpoint_name: &str, intf: &items::Interface) -> proc_m
 
                 let method_payload = &payload[4..];
 
-                match method_id {
+                #(const #selector_idents: u32 = #selectors.selector();)*
+                let result = match method_id {
                     #(#branches,)*
-                    _ => panic!("Invalid method signature"),
-                }
+                    _ => return Err(owasm_abi::eth::Revert::error("Invalid method signature")),
+                };
+                Ok(result)
             }
 
             #[allow(unused_variables)]
             #[allow(unused_mut)]
-            fn dispatch_ctor(&mut self, payload: &[u8]) {
+            fn try_dispatch_ctor(&mut self, payload: &[u8]) -> Result<(), owasm_abi::eth::Revert> {
                 #ctor_branch
+                Ok(())
             }
         }
     }
 }
 
+/// Derive of `AbiType` for a struct, encoding it as a Solidity tuple of its fields,
+/// or for a fieldless enum, encoding it as `uint8` like Solidity enums.
+///
+/// The type can then be used in arguments, return values and event data of
+/// an `eth_abi` interface. Its canonical type, e.g. `(uint256,address)` or `uint8`
+/// respectively, is given by `AbiType::CANONICAL`, which the selectors of the
+/// interface are computed from.
+///
+/// `EncodeInto` is derived along with it, enums also get `AsLog`, `PackedAbiType`
+/// and `PackedMember`.
+///
+/// # Example
+///
+/// ```
+/// #[derive(AbiType)]
+/// struct Payment {
+///     amount: U256,
+///     to: Address,
+/// }
+///
+/// #[derive(AbiType)]
+/// enum Status {
+///     Pending,
+///     Settled,
+/// }
+/// ```
+#[proc_macro_derive(AbiType)]
+pub fn derive_abi_type(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as syn::DeriveInput);
+    abi_type::impl_abi_type(input).into()
+}
+
 #[proc_macro_attribute]
 pub fn contract(
     _args: proc_macro::TokenStream,
@@ -506,7 +913,9 @@ pub fn contract(
         #[cfg(feature = "debug")]
         std::panic::set_hook(Box::new(|panic_info| owasm_std::logger::debug(&format!("PANIC {}", panic_info))));
         let mut endpoint = #contract_ep::new(#contract_struct {});
-        endpoint.dispatch_ctor(&owasm_ethereum::input());
+        if let Err(revert) = endpoint.try_dispatch_ctor(&owasm_ethereum::input()) {
+          revert.abort()
+        }
       }
 
       #[no_mangle]
@@ -514,7 +923,7 @@ pub fn contract(
         #[cfg(feature = "debug")]
         std::panic::set_hook(Box::new(|panic_info| owasm_std::logger::debug(&format!("PANIC {}", panic_info))));
         let mut endpoint = #contract_ep::new(#contract_struct {});
-        owasm_ethereum::ret(&endpoint.dispatch(&owasm_ethereum::input()));
+        endpoint.handle_call(&owasm_ethereum::input(), owasm_ethereum::ret)
       }
     })
 }
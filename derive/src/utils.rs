@@ -1,7 +1,4 @@
 use {syn, quote};
-use tiny_keccak::Keccak;
-use parity_hash::H256;
-use byteorder::{BigEndian, ByteOrder};
 
 pub struct SignatureIterator<'a> {
 	method_sig: &'a syn::MethodSig,
@@ -73,19 +70,40 @@ fn push_int_const_expr(target: &mut String, expr: &syn::Expr) {
 	}
 }
 
+/// Returns `true` if the given type is `u8`.
+fn is_u8(ty: &syn::Type) -> bool {
+	match ty {
+		syn::Type::Path(type_path) => {
+			type_path.qself.is_none()
+				&& type_path.path.segments.last().unwrap().value().ident == "u8"
+		},
+		_ => false,
+	}
+}
+
+/// Returns `true` if the given array type is `[u8; N]` with `1 <= N <= 32`, i.e. `bytesN`.
+///
+/// Longer byte arrays are `uint8[N]`, like any other array.
+fn is_fixed_bytes(type_array: &syn::TypeArray) -> bool {
+	is_u8(&type_array.elem) && match type_array.len {
+		syn::Expr::Lit(syn::ExprLit{lit: syn::Lit::Int(ref lit_int), ..}) => {
+			lit_int.value() >= 1 && lit_int.value() <= 32
+		},
+		_ => false,
+	}
+}
+
 fn push_canonicalized_vec(target: &mut String, args: &syn::PathArguments) {
 	match args {
 		syn::PathArguments::AngleBracketed(gen_args) => {
 			let last_arg = gen_args.args.last().unwrap();
 			let last_type = last_arg.value();
-			if let syn::GenericArgument::Type(syn::Type::Path(type_path)) = last_type {
-				return if type_path.qself.is_none()
-					&& type_path.path.segments.last().unwrap().value().ident == "u8"
-				{
+			if let syn::GenericArgument::Type(elem_type) = last_type {
+				return if is_u8(elem_type) {
 					target.push_str("bytes");
 				}
 				else {
-					push_canonicalized_path(target, type_path);
+					push_canonicalized_type(target, elem_type);
 					target.push_str("[]");
 				}
 			}
@@ -95,22 +113,45 @@ fn push_canonicalized_vec(target: &mut String, args: &syn::PathArguments) {
 	}
 }
 
+fn push_canonicalized_bounded_int(target: &mut String, prefix: &str, args: &syn::PathArguments) {
+	match args {
+		syn::PathArguments::AngleBracketed(gen_args) if gen_args.args.len() == 1 => {
+			match gen_args.args.first().unwrap().value() {
+				syn::GenericArgument::Const(expr) => {
+					target.push_str(prefix);
+					push_int_const_expr(target, expr);
+				},
+				_ => panic!("Integer width should be an integer literal"),
+			}
+		},
+		_ => panic!("Unsupported bounded integer arguments"),
+	}
+}
+
 fn push_canonicalized_primitive(target: &mut String, seg: &syn::PathSegment) {
 	match seg.ident.to_string().as_str() {
+		"u8" => target.push_str("uint8"),
+		"i8" => target.push_str("int8"),
+		"u16" => target.push_str("uint16"),
+		"i16" => target.push_str("int16"),
 		"u32" => target.push_str("uint32"),
 		"i32" => target.push_str("int32"),
 		"u64" => target.push_str("uint64"),
 		"i64" => target.push_str("int64"),
+		"u128" => target.push_str("uint128"),
+		"i128" => target.push_str("int128"),
 		"U256" => target.push_str("uint256"),
+		"I256" => target.push_str("int256"),
 		"H256" => target.push_str("uint256"),
 		"Address" => target.push_str("address"),
 		"String" => target.push_str("string"),
 		"bool" => target.push_str("bool"),
+		"Uint" => push_canonicalized_bounded_int(target, "uint", &seg.arguments),
+		"Int" => push_canonicalized_bounded_int(target, "int", &seg.arguments),
 		"Vec" => push_canonicalized_vec(target, &seg.arguments),
-		val => panic!(
-			"[e1] Unable to handle param of type {}: not supported by abi",
-			val
-		),
+		// types deriving `AbiType` are only known by their name here, which `is_value_type`
+		// takes for enums, the generated code takes their canonical type from `AbiType::CANONICAL`
+		val => target.push_str(val),
 	}
 }
 
@@ -120,6 +161,15 @@ fn push_canonicalized_path(target: &mut String, type_path: &syn::TypePath) {
 	push_canonicalized_primitive(target, *last_path.value())
 }
 
+fn push_canonicalized_tuple<'a, I: Iterator<Item = &'a syn::Type>>(target: &mut String, elems: I) {
+	target.push('(');
+	for (i, elem) in elems.enumerate() {
+		if i != 0 { target.push(','); }
+		push_canonicalized_type(target, elem);
+	}
+	target.push(')');
+}
+
 fn push_canonicalized_type(target: &mut String, ty: &syn::Type) {
 	match ty {
 		syn::Type::Path(type_path) if type_path.qself.is_none() => {
@@ -127,60 +177,175 @@ fn push_canonicalized_type(target: &mut String, ty: &syn::Type) {
 		},
 		syn::Type::Array(type_array) => {
 			// Special cases for `bytesN`
-			if let syn::Type::Path(type_path) = &*type_array.elem {
-				if "u8" == type_path.path.segments.last().unwrap().value().ident.to_string() {
-					target.push_str("bytes");
-					push_int_const_expr(target, &type_array.len);
-					return;
-				}
+			if is_fixed_bytes(type_array) {
+				target.push_str("bytes");
+				push_int_const_expr(target, &type_array.len);
+				return;
 			}
 
-			panic!("Unsupported! Use variable-size arrays")
+			push_canonicalized_type(target, &type_array.elem);
+			target.push('[');
+			push_int_const_expr(target, &type_array.len);
+			target.push(']');
+		},
+		syn::Type::Tuple(type_tuple) => push_canonicalized_tuple(target, type_tuple.elems.iter()),
+		syn::Type::Reference(type_ref) if is_borrowed(ty) => {
+			match *type_ref.elem {
+				syn::Type::Slice(_) => target.push_str("bytes"),
+				_ => target.push_str("string"),
+			}
 		},
 		other_type => panic!("[e2] Unable to handle param of type {:?}: not supported by abi", other_type),
 	}
 }
 
+/// Returns the number of head words occupied by the given types
+/// as a constant expression to be used with `owasm_abi::eth::Sink::new`.
+pub fn head_words<'a, I: IntoIterator<Item = &'a syn::Type>>(types: I) -> proc_macro2::TokenStream {
+	let words = types.into_iter().map(|ty| if is_borrowed(ty) {
+		// borrowed bytes and strings are dynamic
+		quote!{ 1 }
+	} else {
+		quote!{ <#ty as owasm_abi::eth::AbiType>::HEAD_WORDS }
+	});
+	quote!{ 0 #(+ #words)* }
+}
+
+/// Splits `Result<T, E>` into its success type `T` and error type `E`.
+///
+/// Returns `None` for any other type.
+pub fn result_types(ty: &syn::Type) -> Option<(syn::Type, syn::Type)> {
+	let last_seg = match ty {
+		syn::Type::Path(type_path) if type_path.qself.is_none() => {
+			type_path.path.segments.last().unwrap().into_value()
+		},
+		_ => return None,
+	};
+	match last_seg.arguments {
+		syn::PathArguments::AngleBracketed(ref gen_args)
+			if last_seg.ident == "Result" && gen_args.args.len() == 2 =>
+		{
+			match (gen_args.args[0].clone(), gen_args.args[1].clone()) {
+				(syn::GenericArgument::Type(ok_type), syn::GenericArgument::Type(error_type)) => {
+					Some((ok_type, error_type))
+				},
+				_ => None,
+			}
+		},
+		_ => None,
+	}
+}
+
+/// Returns `true` if the given type is `&[u8]` or `&str`,
+/// which are decoded by borrowing from the payload.
+pub fn is_borrowed(ty: &syn::Type) -> bool {
+	match ty {
+		syn::Type::Reference(type_ref) if type_ref.mutability.is_none() => {
+			match *type_ref.elem {
+				syn::Type::Slice(ref type_slice) => is_u8(&type_slice.elem),
+				syn::Type::Path(ref type_path) => {
+					type_path.qself.is_none()
+						&& type_path.path.segments.len() == 1
+						&& type_path.path.segments.last().unwrap().value().ident == "str"
+				},
+				_ => false,
+			}
+		},
+		_ => false,
+	}
+}
+
+/// Returns `true` if the given type is represented by its own encoding when indexed in
+/// an event, and `false` for `bytes`, `string`, arrays and tuples represented by their hash.
+///
+/// Types deriving `AbiType` are taken for value types, as only enums implement `AsLog`.
+pub fn is_value_type(ty: &syn::Type) -> bool {
+	let canonical = canonicalize_type(ty);
+	canonical != "bytes" && canonical != "string" && !canonical.contains(|c| c == '[' || c == '(')
+}
+
+/// Returns the owned counterpart of `&[u8]` and `&str`, or the given type otherwise.
+pub fn owned_type(ty: &syn::Type) -> syn::Type {
+	match ty {
+		syn::Type::Reference(type_ref) if is_borrowed(ty) => match *type_ref.elem {
+			syn::Type::Slice(_) => parse_quote!{ Vec<u8> },
+			_ => parse_quote!{ String },
+		},
+		_ => ty.clone(),
+	}
+}
+
+/// Returns the expression popping the argument of the given index and type from `stream`,
+/// returning an `Error(string)` revert holding the offset, index and ABI type
+/// of the argument if it fails to decode.
+pub fn pop_arg(index: usize, ty: &syn::Type) -> proc_macro2::TokenStream {
+	let pop = if is_borrowed(ty) {
+		quote!{ stream.pop_borrowed::<#ty>() }
+	} else {
+		quote!{ stream.pop::<#ty>() }
+	};
+	let expected = canonical(ty);
+	quote! {
+		#pop
+			.map_err(|err| owasm_abi::eth::Revert::error(&format_args!(
+				"argument decoding failed: {}",
+				stream.error(err).argument(#index, &#expected)
+			)))?
+	}
+}
+
+/// Returns the expression pushing an argument of the given type to `sink`.
+pub fn push_arg(pat: &syn::Pat, ty: &syn::Type) -> proc_macro2::TokenStream {
+	if is_borrowed(ty) {
+		quote!{ sink.push(#pat.to_owned()); }
+	} else {
+		quote!{ sink.push(#pat); }
+	}
+}
+
+/// Converts a snake case name to camel case, i.e. `baz_fired` to `BazFired`.
+///
+/// Names already in camel case are left as they are.
+pub fn camel_case(name: &str) -> String {
+	let mut result = String::new();
+	for part in name.split('_') {
+		let mut chars = part.chars();
+		if let Some(first) = chars.next() {
+			result.extend(first.to_uppercase());
+			result.push_str(chars.as_str());
+		}
+	}
+	result
+}
+
 /// Returns the canonicalized string representation for the given type.
+///
+/// Types deriving `AbiType` are represented by their name.
 pub fn canonicalize_type(ty: &syn::Type) -> String {
 	let mut result = String::new();
 	push_canonicalized_type(&mut result, ty);
 	result
 }
 
-/// Returns the canonicalized string representation for the function
-/// with the given name `name` and method signature `method_sig`.
-/// 
-/// # Note
-/// 
-/// The result can be used by `function_selector` in order to retrieve
-/// the function selector for the associated function.
-pub fn canonicalize_fn(name: &syn::Ident, method_sig: &syn::MethodSig) -> String {
-	let mut s = String::new();
-	s.push_str(&name.to_string());
-	s.push('(');
-	let total_len = method_sig.decl.inputs.len();
-	for (i, (_, ty)) in iter_signature(method_sig).enumerate() {
-		push_canonicalized_type(&mut s, &ty);
-		if i != total_len-2 { s.push(','); }
-	}
-	s.push(')');
-	s
-}
-
-/// Returns the Keccak hash (256-bits) of the given byte slice.
-pub fn keccak(bytes: &[u8]) -> H256 {
-	let mut keccak = Keccak::new_keccak256();
-	let mut res = H256::zero();
-	keccak.update(bytes);
-	keccak.finalize(res.as_mut());
-	res
-}
-
-/// Returns the first 4 bytes of the Keccak hash (256-bits)
-/// for the given canonical function signature representation
-/// represented as string slice.
-pub fn function_selector(canonical_fn_sig: &str) -> u32 {
-	let keccak = keccak(canonical_fn_sig.as_bytes());
-	BigEndian::read_u32(&keccak.as_ref()[0..4])
+/// Returns the expression of the canonical type of the given type,
+/// as given by its `owasm_abi::eth::AbiType::CANONICAL`.
+pub fn canonical(ty: &syn::Type) -> proc_macro2::TokenStream {
+	let ty = owned_type(ty);
+	quote!{ <#ty as owasm_abi::eth::AbiType>::CANONICAL }
+}
+
+/// Returns the constant expression of the canonical signature with the given name and
+/// parameter types, which selectors and event topics are computed from.
+pub fn canonical_signature<'a, I: IntoIterator<Item = &'a syn::Type>>(
+	name: &syn::Ident,
+	types: I,
+) -> proc_macro2::TokenStream {
+	let name = name.to_string();
+	let params = types.into_iter().map(canonical);
+	quote!{
+		owasm_abi::eth::Canonical::Signature {
+			name: #name,
+			params: &[#(#params),*],
+		}
+	}
 }
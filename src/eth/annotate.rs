@@ -0,0 +1,255 @@
+//! Annotated dump of payloads, for debugging
+
+use super::ParamType;
+use lib::*;
+use types::*;
+
+/// Dump payload word by word, with the meaning of each word for the expected types
+///
+/// Payload is expected to start with a 4-byte selector when its length is 4 more
+/// than a multiple of 32. Every following 32-byte word is printed with its offset
+/// (relative to the end of the selector) and what it holds: a head slot, an offset
+/// pointer, a length, or data with its padding. Words no argument accounts for are
+/// flagged with `??`, problems like offsets past the end of the payload are listed
+/// after the words. Arguments from the first one of invalid type on are not annotated.
+pub fn annotate(payload: &[u8], params: &[ParamType]) -> String {
+    let (selector, data) = if payload.len() % 32 == 4 {
+        (Some(&payload[..4]), &payload[4..])
+    } else {
+        (None, payload)
+    };
+
+    let mut annotator = Annotator {
+        data,
+        labels: vec![None; (data.len() + 31) / 32],
+        notes: Vec::new(),
+    };
+    let valid = params
+        .iter()
+        .take_while(|param| param.validate().is_ok())
+        .count();
+    annotator.members(
+        0,
+        params[..valid]
+            .iter()
+            .enumerate()
+            .map(|(i, param)| (param, format!("arg{}", i))),
+    );
+    if valid < params.len() {
+        annotator.notes.push(format!(
+            "arg{} has invalid type {}",
+            valid, params[valid]
+        ));
+    }
+
+    let mut result = String::new();
+    if let Some(selector) = selector {
+        result.push_str(&format!("selector  {}\n", hex(selector)));
+    }
+    for (i, label) in annotator.labels.into_iter().enumerate() {
+        let word = &data[i * 32..cmp::min(data.len(), i * 32 + 32)];
+        let label = match label {
+            Some(label) => label,
+            None if word.len() < 32 => "??      truncated word".to_owned(),
+            None => "??      not accounted for by any argument".to_owned(),
+        };
+        result.push_str(&format!("0x{:04x}    {:64}  {}\n", i * 32, hex(word), label));
+    }
+    for note in annotator.notes {
+        result.push_str(&format!("note: {}\n", note));
+    }
+    result
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+struct Annotator<'a> {
+    data: &'a [u8],
+    labels: Vec<Option<String>>,
+    notes: Vec<String>,
+}
+
+impl<'a> Annotator<'a> {
+    /// Word at `pos`, if it is within the payload
+    fn word(&self, pos: usize) -> Option<&'a [u8]> {
+        let data = self.data;
+        match pos.checked_add(32) {
+            Some(end) if end <= data.len() => Some(&data[pos..end]),
+            _ => None,
+        }
+    }
+
+    /// Read word at `pos` as a length or an offset
+    fn read_usize(&mut self, pos: usize) -> Option<usize> {
+        let value = U256::from_big_endian(self.word(pos)?);
+        if value > U256::from(self.data.len()) {
+            return None;
+        }
+        Some(value.low_u64() as usize)
+    }
+
+    fn label(&mut self, pos: usize, label: String) {
+        if self.word(pos).is_none() {
+            self.notes.push(format!(
+                "0x{:04x} is past the end of the payload: {}",
+                pos, label
+            ));
+            return;
+        }
+        if pos % 32 != 0 {
+            self.notes.push(format!("0x{:04x} is not aligned to a word: {}", pos, label));
+        }
+        let slot = &mut self.labels[pos / 32];
+        *slot = Some(match slot.take() {
+            // several arguments claiming the same word is a sign of bogus offsets
+            Some(previous) => format!("{}; {}", previous, label),
+            None => label,
+        });
+    }
+
+    /// Annotate consecutive members, with offsets of dynamic ones relative to `start`
+    fn members<'p, I>(&mut self, start: usize, members: I)
+    where
+        I: Iterator<Item = (&'p ParamType, String)>,
+    {
+        let mut pos = start;
+        for (param, path) in members {
+            self.param(start, pos, param, &path);
+            pos = pos.saturating_add(param.head_words() * 32);
+        }
+    }
+
+    /// Annotate parameter with its head at `pos`
+    fn param(&mut self, base: usize, pos: usize, param: &ParamType, path: &str) {
+        if param.is_fixed() {
+            return self.fixed(pos, param, path);
+        }
+
+        match self.read_usize(pos) {
+            Some(offset) => {
+                let target = base + offset;
+                self.label(
+                    pos,
+                    format!("offset  {} ({}) -> 0x{:04x}", path, param, target),
+                );
+                self.tail(target, param, path);
+            }
+            None => self.label(
+                pos,
+                format!("offset  {} ({}) points outside of the payload", path, param),
+            ),
+        }
+    }
+
+    fn fixed(&mut self, pos: usize, param: &ParamType, path: &str) {
+        match *param {
+            ParamType::Tuple(ref members) => {
+                let mut pos = pos;
+                for (i, member) in members.iter().enumerate() {
+                    self.fixed(pos, member, &format!("{}.{}", path, i));
+                    pos = pos.saturating_add(member.head_words() * 32);
+                }
+            }
+            ParamType::FixedArray(ref elem, len) => {
+                for i in 0..len {
+                    let pos = pos.saturating_add(i * elem.head_words() * 32);
+                    self.fixed(pos, elem, &format!("{}[{}]", path, i));
+                    if self.word(pos).is_none() {
+                        // the rest of the members are past the end as well
+                        break;
+                    }
+                }
+            }
+            _ => {
+                let label = match self.word(pos) {
+                    Some(word) => format!("head    {} ({}) = {}", path, param, value(word, param)),
+                    None => format!("head    {} ({})", path, param),
+                };
+                self.label(pos, label);
+            }
+        }
+    }
+
+    /// Annotate the tail of dynamic parameter at `pos`
+    fn tail(&mut self, pos: usize, param: &ParamType, path: &str) {
+        match *param {
+            ParamType::Bytes | ParamType::String | ParamType::Array(_) => {
+                let len = match self.read_usize(pos) {
+                    Some(len) => len,
+                    None => {
+                        return self.label(
+                            pos,
+                            format!("length  {} ({}) exceeds the payload", path, param),
+                        )
+                    }
+                };
+                self.label(pos, format!("length  {} ({}) = {}", path, param, len));
+                match *param {
+                    ParamType::Array(ref elem) => {
+                        let size = len
+                            .checked_mul(elem.head_words())
+                            .and_then(|words| words.checked_mul(32));
+                        if size.map_or(true, |size| size > self.data.len()) {
+                            return self.notes.push(format!(
+                                "length of {} ({}) exceeds the payload",
+                                path, param
+                            ));
+                        }
+                        self.members(
+                            pos + 32,
+                            (0..len).map(|i| (&**elem, format!("{}[{}]", path, i))),
+                        )
+                    }
+                    _ => self.data(pos + 32, len, param, path),
+                }
+            }
+            ParamType::FixedArray(ref elem, len) => self.members(
+                pos,
+                (0..len).map(|i| (&**elem, format!("{}[{}]", path, i))),
+            ),
+            ParamType::Tuple(ref members) => self.members(
+                pos,
+                members
+                    .iter()
+                    .enumerate()
+                    .map(|(i, member)| (member, format!("{}.{}", path, i))),
+            ),
+            _ => unreachable!("only dynamic types have a tail"),
+        }
+    }
+
+    /// Annotate `len` bytes of data starting at `pos`, padded to whole words
+    fn data(&mut self, pos: usize, len: usize, param: &ParamType, path: &str) {
+        for i in 0..(len + 31) / 32 {
+            let pos = pos + i * 32;
+            let used = cmp::min(32, len - i * 32);
+            let mut label = format!("data    {} ({})", path, param);
+            if used < 32 {
+                label.push_str(&format!(": {} bytes + padding", used));
+                let padding = self.word(pos).map(|word| &word[used..]);
+                if padding.map_or(false, |padding| padding.iter().any(|byte| *byte != 0)) {
+                    label.push_str(", non-zero padding");
+                }
+            }
+            self.label(pos, label);
+        }
+    }
+}
+
+/// Value of fixed elementary type held by the word
+fn value(word: &[u8], param: &ParamType) -> String {
+    match *param {
+        ParamType::Uint(_) => U256::from_big_endian(word).to_string(),
+        ParamType::Int(_) => I256::from_raw(U256::from_big_endian(word)).to_string(),
+        ParamType::Bool => match U256::from_big_endian(word).low_u64() {
+            0 if word.iter().all(|byte| *byte == 0) => "false".to_owned(),
+            1 if word[..31].iter().all(|byte| *byte == 0) => "true".to_owned(),
+            _ => "invalid bool".to_owned(),
+        },
+        ParamType::Address => format!("0x{}", hex(&word[12..])),
+        ParamType::FixedBytes(len) => format!("0x{}", hex(&word[..len])),
+        _ => unreachable!("only elementary types are held by a single word"),
+    }
+}
@@ -0,0 +1,503 @@
+//! Encoding into a caller-provided buffer, without heap allocation
+
+use super::{common::is_fixed_bytes, sink::CapacityMismatch, types::*, util, AbiType};
+use lib::*;
+
+/// Error for encoding past the end of the buffer
+#[derive(Debug, PartialEq, Eq)]
+pub struct BufferOverflow;
+
+impl fmt::Display for BufferOverflow {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("encoding does not fit into the buffer")
+    }
+}
+
+#[cfg(feature = "std")]
+impl ::std::error::Error for BufferOverflow {}
+
+/// Types which can be encoded into a `BufferSink`
+///
+/// Values are encoded by reference, exactly as their `Owned` type encodes them into `Sink`.
+/// The layout of the encoding, i.e. `IS_FIXED`, `HEAD_WORDS` and `IS_BYTE`, is taken from it.
+pub trait EncodeInto {
+    /// Abi type with the same encoding, like `String` for `str`
+    type Owned: AbiType;
+
+    /// Push type to buffer sink
+    /// Should never be called manually! Use sink.push(&val)
+    fn encode_into(&self, sink: &mut BufferSink) -> Result<(), BufferOverflow>;
+
+    /// Push consecutive members of an array to buffer sink
+    /// Should never be called manually! Used by `[T]`, `Vec<T>` and `[T; N]`
+    #[doc(hidden)]
+    fn encode_members_into(members: &[Self], sink: &mut BufferSink) -> Result<(), BufferOverflow>
+    where
+        Self: Sized,
+    {
+        let head_words = members.len().saturating_mul(Self::Owned::HEAD_WORDS);
+        sink.push_scope(head_words, |sink| {
+            for member in members {
+                sink.push(member)?;
+            }
+            Ok(())
+        })
+    }
+
+    /// Push type encoded in place, as Solidity hashes indexed arguments: with neither
+    /// offsets nor lengths, and `bytes` and `string` padded to 32 bytes
+    /// Should never be called manually! Used by `AsLog`
+    #[doc(hidden)]
+    fn encode_in_place(&self, encoded: &mut Vec<u8>) {
+        encode_static_in_place(self, encoded)
+    }
+
+    /// Push consecutive members of an array encoded in place
+    /// Should never be called manually! Used by `[T]` and `Vec<T>`
+    #[doc(hidden)]
+    fn encode_members_in_place(members: &[Self], encoded: &mut Vec<u8>)
+    where
+        Self: Sized,
+    {
+        for member in members {
+            member.encode_in_place(encoded);
+        }
+    }
+}
+
+/// Sink writing into a caller-provided buffer
+///
+/// Works like `Sink`, but never allocates: fails with `BufferOverflow`
+/// once the encoding does not fit into the buffer.
+pub struct BufferSink<'a> {
+    buffer: &'a mut [u8],
+    capacity: usize,
+    // start of the current sequence of values, offsets are relative to it
+    base: usize,
+    // position of the next word of the current sequence
+    head: usize,
+    // end of everything written so far
+    tail: usize,
+}
+
+impl<'a> BufferSink<'a> {
+    /// New sink with known capacity, fails if the buffer cannot fit it
+    pub fn new(buffer: &'a mut [u8], capacity: usize) -> Result<Self, BufferOverflow> {
+        let capacity = capacity.checked_mul(32).ok_or(BufferOverflow)?;
+        if capacity > buffer.len() {
+            return Err(BufferOverflow);
+        }
+        Ok(BufferSink {
+            buffer,
+            capacity,
+            base: 0,
+            head: 0,
+            tail: capacity,
+        })
+    }
+
+    /// Encode `val` to the Sink
+    pub fn push<T: EncodeInto + ?Sized>(&mut self, val: &T) -> Result<(), BufferOverflow> {
+        if T::Owned::IS_FIXED {
+            return val.encode_into(self);
+        }
+        let offset = self.tail - self.base;
+        self.push_bytes(&util::pad_u32(offset as u32))?;
+
+        // dynamic value goes to the end of the buffer, its own members are relative to it
+        let (base, head) = (self.base, self.head);
+        self.base = self.tail;
+        self.head = self.tail;
+        let result = val.encode_into(self);
+        self.base = base;
+        self.head = head;
+        result
+    }
+
+    /// Encode nested sequence of values taking `head_words` words, like tuple members
+    pub fn push_scope<F>(&mut self, head_words: usize, encode: F) -> Result<(), BufferOverflow>
+    where
+        F: FnOnce(&mut BufferSink<'a>) -> Result<(), BufferOverflow>,
+    {
+        let end = self.reserve(head_words.checked_mul(32).ok_or(BufferOverflow)?)?;
+        let base = self.base;
+        self.base = self.head;
+        self.tail = cmp::max(self.tail, end);
+        let result = encode(self);
+        self.base = base;
+        self.head = end;
+        result
+    }
+
+    /// Write bytes at the current position, padded with zeros to a multiple of 32 bytes
+    pub fn push_bytes(&mut self, bytes: &[u8]) -> Result<(), BufferOverflow> {
+        let padded_len = bytes.len().checked_add(31).ok_or(BufferOverflow)? / 32 * 32;
+        let end = self.reserve(padded_len)?;
+        let (data, padding) = self.buffer[self.head..end].split_at_mut(bytes.len());
+        data.copy_from_slice(bytes);
+        for byte in padding {
+            *byte = 0;
+        }
+        self.head = end;
+        self.tail = cmp::max(self.tail, end);
+        Ok(())
+    }
+
+    fn reserve(&self, len: usize) -> Result<usize, BufferOverflow> {
+        match self.head.checked_add(len) {
+            Some(end) if end <= self.buffer.len() => Ok(end),
+            _ => Err(BufferOverflow),
+        }
+    }
+
+    /// Number of bytes written to the buffer so far
+    pub fn len(&self) -> usize {
+        self.tail
+    }
+
+    /// Whether nothing was written to the buffer yet
+    pub fn is_empty(&self) -> bool {
+        self.tail == 0
+    }
+
+    /// Consume current Sink to produce the number of bytes written.
+    /// Fails if declared number of arguments does not match the number of words pushed.
+    pub fn finalize(self) -> Result<usize, CapacityMismatch> {
+        if self.head != self.capacity {
+            return Err(CapacityMismatch {
+                expected: self.capacity / 32,
+                actual: (self.head + 31) / 32,
+            });
+        }
+        Ok(self.tail)
+    }
+}
+
+/// Encode single value into the buffer, returns the number of bytes written
+pub fn encode_into<T: EncodeInto + ?Sized>(
+    val: &T,
+    buffer: &mut [u8],
+) -> Result<usize, BufferOverflow> {
+    let mut sink = BufferSink::new(buffer, T::Owned::HEAD_WORDS)?;
+    sink.push(val)?;
+    Ok(sink.len())
+}
+
+/// Static types are encoded in place by their standard encoding
+fn encode_static_in_place<T: EncodeInto + ?Sized>(val: &T, encoded: &mut Vec<u8>) {
+    let start = encoded.len();
+    encoded.resize(start + T::Owned::HEAD_WORDS * 32, 0);
+    encode_into(val, &mut encoded[start..]).expect("static types fit their head words");
+}
+
+macro_rules! encode_into_int_impl {
+    ($pad: path, $($t: ty),*) => {
+        $(
+            impl EncodeInto for $t {
+                type Owned = $t;
+
+                fn encode_into(&self, sink: &mut BufferSink) -> Result<(), BufferOverflow> {
+                    sink.push_bytes(&$pad(&self.to_be_bytes()))
+                }
+            }
+        )*
+    };
+}
+
+encode_into_int_impl!(util::pad_unsigned, u16, u32, u64, u128);
+encode_into_int_impl!(util::pad_signed, i8, i16, i32, i64, i128);
+
+impl EncodeInto for u8 {
+    type Owned = u8;
+
+    fn encode_into(&self, sink: &mut BufferSink) -> Result<(), BufferOverflow> {
+        sink.push_bytes(&util::pad_unsigned(&[*self]))
+    }
+
+    // `[u8]` and `[u8; N]` are packed as `bytes` and `bytesN` respectively
+    fn encode_members_into(members: &[Self], sink: &mut BufferSink) -> Result<(), BufferOverflow> {
+        sink.push_bytes(members)
+    }
+
+    fn encode_members_in_place(members: &[Self], encoded: &mut Vec<u8>) {
+        let padded_len = (members.len() + 31) / 32 * 32;
+        encoded.extend_from_slice(members);
+        encoded.resize(encoded.len() + padded_len - members.len(), 0);
+    }
+}
+
+impl EncodeInto for bool {
+    type Owned = bool;
+
+    fn encode_into(&self, sink: &mut BufferSink) -> Result<(), BufferOverflow> {
+        sink.push_bytes(&util::pad_u32(*self as u32))
+    }
+}
+
+impl EncodeInto for U256 {
+    type Owned = U256;
+
+    fn encode_into(&self, sink: &mut BufferSink) -> Result<(), BufferOverflow> {
+        let mut word = [0u8; 32];
+        self.to_big_endian(&mut word);
+        sink.push_bytes(&word)
+    }
+}
+
+impl EncodeInto for I256 {
+    type Owned = I256;
+
+    fn encode_into(&self, sink: &mut BufferSink) -> Result<(), BufferOverflow> {
+        self.into_raw().encode_into(sink)
+    }
+}
+
+impl<const BITS: usize> EncodeInto for Uint<BITS> {
+    type Owned = Uint<BITS>;
+
+    fn encode_into(&self, sink: &mut BufferSink) -> Result<(), BufferOverflow> {
+        self.value().encode_into(sink)
+    }
+}
+
+impl<const BITS: usize> EncodeInto for Int<BITS> {
+    type Owned = Int<BITS>;
+
+    fn encode_into(&self, sink: &mut BufferSink) -> Result<(), BufferOverflow> {
+        self.value().encode_into(sink)
+    }
+}
+
+impl EncodeInto for Address {
+    type Owned = Address;
+
+    fn encode_into(&self, sink: &mut BufferSink) -> Result<(), BufferOverflow> {
+        sink.push_bytes(&util::pad_unsigned(self.as_ref()))
+    }
+}
+
+impl EncodeInto for H256 {
+    type Owned = H256;
+
+    fn encode_into(&self, sink: &mut BufferSink) -> Result<(), BufferOverflow> {
+        sink.push_bytes(self.as_ref())
+    }
+}
+
+impl EncodeInto for str {
+    type Owned = String;
+
+    fn encode_into(&self, sink: &mut BufferSink) -> Result<(), BufferOverflow> {
+        self.as_bytes().encode_into(sink)
+    }
+
+    fn encode_in_place(&self, encoded: &mut Vec<u8>) {
+        self.as_bytes().encode_in_place(encoded)
+    }
+}
+
+impl EncodeInto for String {
+    type Owned = String;
+
+    fn encode_into(&self, sink: &mut BufferSink) -> Result<(), BufferOverflow> {
+        self.as_str().encode_into(sink)
+    }
+
+    fn encode_in_place(&self, encoded: &mut Vec<u8>) {
+        self.as_str().encode_in_place(encoded)
+    }
+}
+
+impl<T: EncodeInto> EncodeInto for [T] {
+    type Owned = Vec<T::Owned>;
+
+    fn encode_into(&self, sink: &mut BufferSink) -> Result<(), BufferOverflow> {
+        sink.push(&(self.len() as u32))?;
+        T::encode_members_into(self, sink)
+    }
+
+    fn encode_in_place(&self, encoded: &mut Vec<u8>) {
+        T::encode_members_in_place(self, encoded)
+    }
+}
+
+impl<T: EncodeInto> EncodeInto for Vec<T> {
+    type Owned = Vec<T::Owned>;
+
+    fn encode_into(&self, sink: &mut BufferSink) -> Result<(), BufferOverflow> {
+        self.as_slice().encode_into(sink)
+    }
+
+    fn encode_in_place(&self, encoded: &mut Vec<u8>) {
+        self.as_slice().encode_in_place(encoded)
+    }
+}
+
+impl<T: EncodeInto, const N: usize> EncodeInto for [T; N] {
+    type Owned = [T::Owned; N];
+
+    fn encode_into(&self, sink: &mut BufferSink) -> Result<(), BufferOverflow> {
+        if T::Owned::IS_BYTE && !is_fixed_bytes::<T::Owned>(N) {
+            // longer `[u8; N]` are `uint8[N]`, with one word per member
+            for member in self {
+                sink.push(member)?;
+            }
+            Ok(())
+        } else {
+            T::encode_members_into(self, sink)
+        }
+    }
+
+    fn encode_in_place(&self, encoded: &mut Vec<u8>) {
+        if T::Owned::IS_FIXED {
+            encode_static_in_place(self, encoded)
+        } else {
+            for member in self {
+                member.encode_in_place(encoded);
+            }
+        }
+    }
+}
+
+impl<'b, T: EncodeInto + ?Sized> EncodeInto for &'b T {
+    type Owned = T::Owned;
+
+    fn encode_into(&self, sink: &mut BufferSink) -> Result<(), BufferOverflow> {
+        (**self).encode_into(sink)
+    }
+
+    fn encode_in_place(&self, encoded: &mut Vec<u8>) {
+        (**self).encode_in_place(encoded)
+    }
+}
+
+macro_rules! encode_into_tuple_impls {
+	($(
+		$Tuple:ident {
+			$(($idx:tt) -> $T:ident)+
+		}
+	)+) => {
+		$(
+			impl<$($T:EncodeInto),+> EncodeInto for ($($T,)+) {
+				type Owned = ($($T::Owned,)+);
+
+				fn encode_into(&self, sink: &mut BufferSink) -> Result<(), BufferOverflow> {
+					// offsets of dynamic members are relative to the start of the tuple
+					sink.push_scope(0 $(+ <$T::Owned as AbiType>::HEAD_WORDS)+, |sink| {
+						$(sink.push(&self.$idx)?;)+
+						Ok(())
+					})
+				}
+
+				fn encode_in_place(&self, encoded: &mut Vec<u8>) {
+					$(self.$idx.encode_in_place(encoded);)+
+				}
+			}
+		)+
+	}
+}
+
+encode_into_tuple_impls! {
+    Tuple1 {
+        (0) -> A
+    }
+    Tuple2 {
+        (0) -> A
+        (1) -> B
+    }
+    Tuple3 {
+        (0) -> A
+        (1) -> B
+        (2) -> C
+    }
+    Tuple4 {
+        (0) -> A
+        (1) -> B
+        (2) -> C
+        (3) -> D
+    }
+    Tuple5 {
+        (0) -> A
+        (1) -> B
+        (2) -> C
+        (3) -> D
+        (4) -> E
+    }
+    Tuple6 {
+        (0) -> A
+        (1) -> B
+        (2) -> C
+        (3) -> D
+        (4) -> E
+        (5) -> F
+    }
+    Tuple7 {
+        (0) -> A
+        (1) -> B
+        (2) -> C
+        (3) -> D
+        (4) -> E
+        (5) -> F
+        (6) -> G
+    }
+    Tuple8 {
+        (0) -> A
+        (1) -> B
+        (2) -> C
+        (3) -> D
+        (4) -> E
+        (5) -> F
+        (6) -> G
+        (7) -> H
+    }
+    Tuple9 {
+        (0) -> A
+        (1) -> B
+        (2) -> C
+        (3) -> D
+        (4) -> E
+        (5) -> F
+        (6) -> G
+        (7) -> H
+        (8) -> I
+    }
+    Tuple10 {
+        (0) -> A
+        (1) -> B
+        (2) -> C
+        (3) -> D
+        (4) -> E
+        (5) -> F
+        (6) -> G
+        (7) -> H
+        (8) -> I
+        (9) -> J
+    }
+    Tuple11 {
+        (0) -> A
+        (1) -> B
+        (2) -> C
+        (3) -> D
+        (4) -> E
+        (5) -> F
+        (6) -> G
+        (7) -> H
+        (8) -> I
+        (9) -> J
+        (10) -> K
+    }
+    Tuple12 {
+        (0) -> A
+        (1) -> B
+        (2) -> C
+        (3) -> D
+        (4) -> E
+        (5) -> F
+        (6) -> G
+        (7) -> H
+        (8) -> I
+        (9) -> J
+        (10) -> K
+        (11) -> L
+    }
+}
@@ -0,0 +1,167 @@
+//! Canonical types, as they appear in signatures
+
+use super::hash::Sponge;
+use lib::*;
+
+/// Canonical type of a parameter, like `uint256[]` or `(address,bytes32)`,
+/// or a whole signature made of them
+///
+/// Every `AbiType` has one in `AbiType::CANONICAL`, composed from the canonical
+/// types of its members, so that selectors and event topics can be computed in
+/// constants even for types the `eth_abi` macro knows nothing about.
+///
+/// ```
+/// # use owasm_abi::eth::{AbiType, Canonical};
+/// # use owasm_abi::types::*;
+/// const TRANSFER: Canonical = Canonical::Signature {
+///     name: "transfer",
+///     params: &[<Address as AbiType>::CANONICAL, <U256 as AbiType>::CANONICAL],
+/// };
+/// assert_eq!(TRANSFER.to_string(), "transfer(address,uint256)");
+/// assert_eq!(TRANSFER.selector(), 0xa9059cbb);
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub enum Canonical {
+    /// Type named as is, like `address` or `string`
+    Elementary {
+        /// Name of the type
+        name: &'static str,
+    },
+    /// `uint<bits>`
+    Uint {
+        /// Width of the integer
+        bits: usize,
+    },
+    /// `int<bits>`
+    Int {
+        /// Width of the integer
+        bits: usize,
+    },
+    /// `T[]`, or `bytes` for arrays of bytes
+    Array {
+        /// Canonical type of the members
+        elem: &'static Canonical,
+        /// Whether the members are bytes
+        is_byte: bool,
+    },
+    /// `T[len]`, or `bytes<len>` for arrays of 1 to 32 bytes
+    FixedArray {
+        /// Canonical type of the members
+        elem: &'static Canonical,
+        /// Number of members
+        len: usize,
+        /// Whether the members are bytes
+        is_byte: bool,
+    },
+    /// `(T1,T2,...)`
+    Tuple {
+        /// Canonical types of the members
+        members: &'static [Canonical],
+        /// Names of the members, like the fields of a struct, empty if they have none
+        ///
+        /// Only the JSON ABI lists them, they aren't part of the canonical string.
+        names: &'static [&'static str],
+    },
+    /// Signature of a function, event or error, like `transfer(address,uint256)`
+    Signature {
+        /// Name of the function, event or error
+        name: &'static str,
+        /// Canonical types of the parameters
+        params: &'static [Canonical],
+    },
+}
+
+impl Canonical {
+    /// Keccak-256 hash of the canonical string, e.g. the first topic of event logs
+    pub const fn hash(&self) -> [u8; 32] {
+        absorb(Sponge::new(), self).finish()
+    }
+
+    /// Function selector: first 4 bytes of the Keccak hash of the canonical string
+    pub const fn selector(&self) -> u32 {
+        let hash = self.hash();
+        u32::from_be_bytes([hash[0], hash[1], hash[2], hash[3]])
+    }
+}
+
+const fn absorb(sponge: Sponge, canonical: &Canonical) -> Sponge {
+    match *canonical {
+        Canonical::Elementary { name } => sponge.absorb(name.as_bytes()),
+        Canonical::Uint { bits } => absorb_number(sponge.absorb("uint".as_bytes()), bits),
+        Canonical::Int { bits } => absorb_number(sponge.absorb("int".as_bytes()), bits),
+        Canonical::Array { is_byte: true, .. } => sponge.absorb("bytes".as_bytes()),
+        Canonical::Array { elem, .. } => absorb(sponge, elem).absorb("[]".as_bytes()),
+        Canonical::FixedArray { elem, len, is_byte } => {
+            if is_byte && len >= 1 && len <= 32 {
+                absorb_number(sponge.absorb("bytes".as_bytes()), len)
+            } else {
+                absorb_number(absorb(sponge, elem).absorb_byte(b'['), len).absorb_byte(b']')
+            }
+        }
+        Canonical::Tuple { members, .. } => absorb_members(sponge, members),
+        Canonical::Signature { name, params } => {
+            absorb_members(sponge.absorb(name.as_bytes()), params)
+        }
+    }
+}
+
+const fn absorb_members(mut sponge: Sponge, members: &[Canonical]) -> Sponge {
+    sponge = sponge.absorb_byte(b'(');
+    let mut i = 0;
+    while i < members.len() {
+        if i != 0 {
+            sponge = sponge.absorb_byte(b',');
+        }
+        sponge = absorb(sponge, &members[i]);
+        i += 1;
+    }
+    sponge.absorb_byte(b')')
+}
+
+const fn absorb_number(mut sponge: Sponge, number: usize) -> Sponge {
+    let mut divisor = 1;
+    while number / divisor >= 10 {
+        divisor *= 10;
+    }
+    while divisor > 0 {
+        sponge = sponge.absorb_byte(b'0' + (number / divisor % 10) as u8);
+        divisor /= 10;
+    }
+    sponge
+}
+
+impl fmt::Display for Canonical {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Canonical::Elementary { name } => f.write_str(name),
+            Canonical::Uint { bits } => write!(f, "uint{}", bits),
+            Canonical::Int { bits } => write!(f, "int{}", bits),
+            Canonical::Array { is_byte: true, .. } => f.write_str("bytes"),
+            Canonical::Array { elem, .. } => write!(f, "{}[]", elem),
+            Canonical::FixedArray { elem, len, is_byte } => {
+                if is_byte && len >= 1 && len <= 32 {
+                    write!(f, "bytes{}", len)
+                } else {
+                    write!(f, "{}[{}]", elem, len)
+                }
+            }
+            Canonical::Tuple { members, .. } => write_members(f, members),
+            Canonical::Signature { name, params } => {
+                f.write_str(name)?;
+                write_members(f, params)
+            }
+        }
+    }
+}
+
+fn write_members(f: &mut fmt::Formatter, members: &[Canonical]) -> fmt::Result {
+    f.write_str("(")?;
+    for (i, member) in members.iter().enumerate() {
+        if i != 0 {
+            f.write_str(",")?;
+        }
+        fmt::Display::fmt(member, f)?;
+    }
+    f.write_str(")")
+}
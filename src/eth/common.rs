@@ -1,6 +1,6 @@
 //! Common types encoding/decoding
 
-use super::{types::*, util, AbiType, Error, Sink, Stream};
+use super::{types::*, util, AbiType, BorrowedAbiType, Canonical, Error, Sink, Stream};
 use lib::*;
 
 impl AbiType for u32 {
@@ -27,6 +27,8 @@ impl AbiType for u32 {
     }
 
     const IS_FIXED: bool = true;
+
+    const CANONICAL: Canonical = Canonical::Uint { bits: 32 };
 }
 
 impl AbiType for u64 {
@@ -57,30 +59,37 @@ impl AbiType for u64 {
     }
 
     const IS_FIXED: bool = true;
+
+    const CANONICAL: Canonical = Canonical::Uint { bits: 64 };
 }
 
-impl AbiType for Vec<u8> {
+impl AbiType for String {
     fn decode(stream: &mut Stream) -> Result<Self, Error> {
-        let len = u32::decode(stream)? as usize;
-
-        let result = stream.payload()[stream.position()..stream.position() + len].to_vec();
-        stream.advance(len)?;
-        stream.finish_advance();
-
-        Ok(result)
+        let bytes = Vec::<u8>::decode(stream)?;
+        String::from_utf8(bytes).map_err(|_| Error::InvalidUtf8)
     }
 
     fn encode(self, sink: &mut Sink) {
-        let mut val = self;
-        let len = val.len();
-        if len % 32 != 0 {
-            val.resize(len + (32 - len % 32), 0);
-        }
-        sink.push(len as u32);
-        sink.preamble_mut().extend_from_slice(&val[..]);
+        self.into_bytes().encode(sink)
     }
 
     const IS_FIXED: bool = false;
+
+    const CANONICAL: Canonical = Canonical::Elementary { name: "string" };
+}
+
+impl<'a> BorrowedAbiType<'a> for &'a [u8] {
+    fn decode(stream: &mut Stream<'a>) -> Result<Self, Error> {
+        let len = u32::decode(stream)? as usize;
+        stream.take(len)
+    }
+}
+
+impl<'a> BorrowedAbiType<'a> for &'a str {
+    fn decode(stream: &mut Stream<'a>) -> Result<Self, Error> {
+        let bytes = <&[u8]>::decode(stream)?;
+        str::from_utf8(bytes).map_err(|_| Error::InvalidUtf8)
+    }
 }
 
 impl AbiType for bool {
@@ -103,6 +112,8 @@ impl AbiType for bool {
     }
 
     const IS_FIXED: bool = true;
+
+    const CANONICAL: Canonical = Canonical::Elementary { name: "bool" };
 }
 
 impl AbiType for U256 {
@@ -121,11 +132,58 @@ impl AbiType for U256 {
     }
 
     const IS_FIXED: bool = true;
+
+    const CANONICAL: Canonical = Canonical::Uint { bits: 256 };
+}
+
+impl AbiType for I256 {
+    fn decode(stream: &mut Stream) -> Result<Self, Error> {
+        Ok(I256::from_raw(U256::decode(stream)?))
+    }
+
+    fn encode(self, sink: &mut Sink) {
+        self.into_raw().encode(sink)
+    }
+
+    const IS_FIXED: bool = true;
+
+    const CANONICAL: Canonical = Canonical::Int { bits: 256 };
+}
+
+impl<const BITS: usize> AbiType for Uint<BITS> {
+    fn decode(stream: &mut Stream) -> Result<Self, Error> {
+        Uint::new(U256::decode(stream)?).map_err(|_| Error::IntegerOverflow)
+    }
+
+    fn encode(self, sink: &mut Sink) {
+        self.value().encode(sink)
+    }
+
+    const IS_FIXED: bool = true;
+
+    const CANONICAL: Canonical = Canonical::Uint { bits: BITS };
+}
+
+impl<const BITS: usize> AbiType for Int<BITS> {
+    fn decode(stream: &mut Stream) -> Result<Self, Error> {
+        Int::new(I256::decode(stream)?).map_err(|_| Error::IntegerOverflow)
+    }
+
+    fn encode(self, sink: &mut Sink) {
+        self.value().encode(sink)
+    }
+
+    const IS_FIXED: bool = true;
+
+    const CANONICAL: Canonical = Canonical::Int { bits: BITS };
 }
 
 impl AbiType for Address {
     fn decode(stream: &mut Stream) -> Result<Self, Error> {
         let arr = <H256>::decode(stream)?;
+        if stream.is_strict() && arr[0..12].iter().any(|b| *b != 0) {
+            return Err(Error::InvalidPadding);
+        }
         Ok(arr.into())
     }
 
@@ -134,6 +192,8 @@ impl AbiType for Address {
     }
 
     const IS_FIXED: bool = true;
+
+    const CANONICAL: Canonical = Canonical::Elementary { name: "address" };
 }
 
 impl AbiType for H256 {
@@ -147,32 +207,32 @@ impl AbiType for H256 {
     }
 
     const IS_FIXED: bool = true;
+
+    const CANONICAL: Canonical = Canonical::Uint { bits: 256 };
 }
 
 impl<T: AbiType> AbiType for Vec<T> {
     fn decode(stream: &mut Stream) -> Result<Self, Error> {
         let len = u32::decode(stream)? as usize;
-        let mut result = Vec::with_capacity(len);
-        for _ in 0..len {
-            result.push(stream.pop()?);
-        }
-        Ok(result)
+        T::decode_members(stream, len)
     }
 
     fn encode(self, sink: &mut Sink) {
         sink.push(self.len() as u32);
-
-        for member in self.into_iter() {
-            sink.push(member);
-        }
+        T::encode_members(self, sink);
     }
 
     const IS_FIXED: bool = false;
+
+    const CANONICAL: Canonical = Canonical::Array {
+        elem: &T::CANONICAL,
+        is_byte: T::IS_BYTE,
+    };
 }
 
 impl AbiType for i32 {
     fn decode(stream: &mut Stream) -> Result<Self, Error> {
-        let is_negative = stream.peek() & 0x80 != 0;
+        let is_negative = stream.peek()? & 0x80 != 0;
 
         if !is_negative {
             return Ok(u32::decode(stream)? as i32);
@@ -201,11 +261,13 @@ impl AbiType for i32 {
     }
 
     const IS_FIXED: bool = true;
+
+    const CANONICAL: Canonical = Canonical::Int { bits: 32 };
 }
 
 impl AbiType for i64 {
     fn decode(stream: &mut Stream) -> Result<Self, Error> {
-        let is_negative = stream.peek() & 0x80 != 0;
+        let is_negative = stream.peek()? & 0x80 != 0;
 
         if !is_negative {
             return Ok(u64::decode(stream)? as i64);
@@ -238,30 +300,145 @@ impl AbiType for i64 {
     }
 
     const IS_FIXED: bool = true;
+
+    const CANONICAL: Canonical = Canonical::Int { bits: 64 };
 }
 
-macro_rules! abi_type_fixed_impl {
-    ($num: expr) => {
-        impl AbiType for [u8; $num] {
+macro_rules! abi_type_unsigned_impl {
+    ($t: ty, $bytes: expr) => {
+        abi_type_unsigned_impl!($t, $bytes, {});
+    };
+    ($t: ty, $bytes: expr, { $($extra: tt)* }) => {
+        impl AbiType for $t {
             fn decode(stream: &mut Stream) -> Result<Self, Error> {
                 let previous_position = stream.advance(32)?;
+
                 let slice = &stream.payload()[previous_position..stream.position()];
-                let mut result = [0u8; $num];
-                result.copy_from_slice(&slice[0..$num]);
-                Ok(result)
+
+                if !slice[..32 - $bytes].iter().all(|x| *x == 0) {
+                    return Err(Error::InvalidPadding);
+                }
+
+                let mut bytes = [0u8; $bytes];
+                bytes.copy_from_slice(&slice[32 - $bytes..]);
+                Ok(<$t>::from_be_bytes(bytes))
             }
 
             fn encode(self, sink: &mut Sink) {
-                let mut padded = [0u8; 32];
-                padded[0..$num].copy_from_slice(&self[..]);
-                sink.preamble_mut().extend_from_slice(&padded[..]);
+                sink.preamble_mut()
+                    .extend_from_slice(&util::pad_unsigned(&self.to_be_bytes())[..]);
             }
 
             const IS_FIXED: bool = true;
+
+            const CANONICAL: Canonical = Canonical::Uint { bits: $bytes * 8 };
+
+            $($extra)*
         }
     };
 }
 
+macro_rules! abi_type_signed_impl {
+    ($t: ty, $bytes: expr) => {
+        impl AbiType for $t {
+            fn decode(stream: &mut Stream) -> Result<Self, Error> {
+                let previous_position = stream.advance(32)?;
+
+                let slice = &stream.payload()[previous_position..stream.position()];
+
+                // padding must be the sign extension of the value
+                let padding = if slice[32 - $bytes] & 0x80 != 0 { 0xff } else { 0 };
+                if !slice[..32 - $bytes].iter().all(|x| *x == padding) {
+                    return Err(Error::InvalidPadding);
+                }
+
+                let mut bytes = [0u8; $bytes];
+                bytes.copy_from_slice(&slice[32 - $bytes..]);
+                Ok(<$t>::from_be_bytes(bytes))
+            }
+
+            fn encode(self, sink: &mut Sink) {
+                sink.preamble_mut()
+                    .extend_from_slice(&util::pad_signed(&self.to_be_bytes())[..]);
+            }
+
+            const IS_FIXED: bool = true;
+
+            const CANONICAL: Canonical = Canonical::Int { bits: $bytes * 8 };
+        }
+    };
+}
+
+/// `[u8; N]` is packed as `bytesN` for `1 <= N <= 32`, longer arrays are `uint8[N]`
+/// with one word per member
+pub(crate) fn is_fixed_bytes<T: AbiType>(len: usize) -> bool {
+    T::IS_BYTE && len >= 1 && len <= 32
+}
+
+// `Vec<u8>` and `[u8; N]` are packed as `bytes` and `bytesN` respectively
+abi_type_unsigned_impl!(u8, 1, {
+    const IS_BYTE: bool = true;
+
+    fn decode_members(stream: &mut Stream, len: usize) -> Result<Vec<Self>, Error> {
+        Ok(stream.take(len)?.to_vec())
+    }
+
+    fn encode_members(members: Vec<Self>, sink: &mut Sink) {
+        let mut val = members;
+        let len = val.len();
+        if len % 32 != 0 {
+            val.resize(len + (32 - len % 32), 0);
+        }
+        sink.preamble_mut().extend_from_slice(&val[..]);
+    }
+});
+abi_type_unsigned_impl!(u16, 2);
+abi_type_unsigned_impl!(u128, 16);
+abi_type_signed_impl!(i8, 1);
+abi_type_signed_impl!(i16, 2);
+abi_type_signed_impl!(i128, 16);
+
+impl<T: AbiType, const N: usize> AbiType for [T; N] {
+    fn decode(stream: &mut Stream) -> Result<Self, Error> {
+        let members = if T::IS_BYTE && !is_fixed_bytes::<T>(N) {
+            let mut members = Vec::with_capacity(N);
+            for _ in 0..N {
+                members.push(stream.pop::<T>()?);
+            }
+            members
+        } else {
+            T::decode_members(stream, N)?
+        };
+        convert::TryFrom::try_from(members).map_err(|_| Error::Other)
+    }
+
+    fn encode(self, sink: &mut Sink) {
+        if T::IS_BYTE && !is_fixed_bytes::<T>(N) {
+            for member in Vec::from(self) {
+                sink.push(member);
+            }
+        } else {
+            T::encode_members(Vec::from(self), sink);
+        }
+    }
+
+    const IS_FIXED: bool = T::IS_FIXED;
+
+    const CANONICAL: Canonical = Canonical::FixedArray {
+        elem: &T::CANONICAL,
+        len: N,
+        is_byte: T::IS_BYTE,
+    };
+
+    const HEAD_WORDS: usize = if T::IS_BYTE && N >= 1 && N <= 32 {
+        1
+    } else if T::IS_FIXED {
+        N * T::HEAD_WORDS
+    } else {
+        1
+    };
+}
+
 macro_rules! tuple_impls {
 	($(
 		$Tuple:ident {
@@ -270,16 +447,29 @@ macro_rules! tuple_impls {
 	)+) => {
 		$(
 			impl<$($T:AbiType),+> AbiType for ($($T,)+) {
-				fn decode(_stream: &mut Stream) -> Result<Self, Error> {
-					panic!("Tuples allow only encoding, not decoding (for supporting multiple return types)")
+				fn decode(stream: &mut Stream) -> Result<Self, Error> {
+					Ok(($(stream.pop::<$T>()?,)+))
 				}
 
 				fn encode(self, sink: &mut Sink) {
-					$(sink.push(self.$idx);)+
+					if Self::IS_FIXED {
+						$(sink.push(self.$idx);)+
+					} else {
+						// offsets of dynamic members are relative to the start of the tuple
+						let mut nested_sink = Sink::new(0 $(+ $T::HEAD_WORDS)+);
+						$(nested_sink.push(self.$idx);)+
+						nested_sink.drain_to(sink.preamble_mut());
+					}
 				}
 
-				const IS_FIXED: bool = true;
+				const IS_FIXED: bool = true $(&& $T::IS_FIXED)+;
+
+				const HEAD_WORDS: usize = if Self::IS_FIXED { 0 $(+ $T::HEAD_WORDS)+ } else { 1 };
 
+				const CANONICAL: Canonical = Canonical::Tuple {
+					members: &[$($T::CANONICAL),+],
+					names: &[],
+				};
 			}
 		)+
 	}
@@ -390,39 +580,6 @@ tuple_impls! {
     }
 }
 
-abi_type_fixed_impl!(1);
-abi_type_fixed_impl!(2);
-abi_type_fixed_impl!(3);
-abi_type_fixed_impl!(4);
-abi_type_fixed_impl!(5);
-abi_type_fixed_impl!(6);
-abi_type_fixed_impl!(7);
-abi_type_fixed_impl!(8);
-abi_type_fixed_impl!(9);
-abi_type_fixed_impl!(10);
-abi_type_fixed_impl!(11);
-abi_type_fixed_impl!(12);
-abi_type_fixed_impl!(13);
-abi_type_fixed_impl!(14);
-abi_type_fixed_impl!(15);
-abi_type_fixed_impl!(16);
-abi_type_fixed_impl!(17);
-abi_type_fixed_impl!(18);
-abi_type_fixed_impl!(19);
-abi_type_fixed_impl!(20);
-abi_type_fixed_impl!(21);
-abi_type_fixed_impl!(22);
-abi_type_fixed_impl!(23);
-abi_type_fixed_impl!(24);
-abi_type_fixed_impl!(25);
-abi_type_fixed_impl!(26);
-abi_type_fixed_impl!(27);
-abi_type_fixed_impl!(28);
-abi_type_fixed_impl!(29);
-abi_type_fixed_impl!(30);
-abi_type_fixed_impl!(31);
-abi_type_fixed_impl!(32);
-
 #[cfg(test)]
 mod tests {
 
@@ -0,0 +1,768 @@
+//! Serde data format backed by the ABI codec
+//!
+//! Structs and tuples map to ABI tuples, sequences to dynamic arrays, byte
+//! buffers and sequences of `u8` to `bytes`, arrays of up to 32 `u8` to `bytesN`
+//! and unit enum variants to `uint8`, as the `AbiType` impls do. Values go through
+//! `AbiValue`, so they are encoded by `Sink` and decoded by `Stream` exactly
+//! like the hand-written impls.
+
+use super::{encode_values, AbiValue, DecodeError, ParamType, Stream};
+use int::{fits_unsigned, u256_from_u128, u256_low_u128};
+use lib::*;
+use serde::de::{self, value::SeqDeserializer, DeserializeOwned, IntoDeserializer, Visitor};
+use serde::ser::{self, Impossible, Serialize};
+use types::*;
+
+/// Error of the serde data format
+#[derive(Debug, Clone, PartialEq)]
+pub enum SerdeError {
+    /// Type has no ABI counterpart, like floats, options and maps
+    Unsupported(&'static str),
+    /// Payload failed to decode
+    Decode(DecodeError),
+    /// Error raised by the `Serialize` or `Deserialize` impl
+    Custom(String),
+}
+
+impl fmt::Display for SerdeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SerdeError::Unsupported(what) => write!(f, "{} are not supported by the ABI", what),
+            SerdeError::Decode(ref err) => write!(f, "{}", err),
+            SerdeError::Custom(ref msg) => f.write_str(msg),
+        }
+    }
+}
+
+impl ser::StdError for SerdeError {}
+
+impl ser::Error for SerdeError {
+    fn custom<T: Display>(msg: T) -> Self {
+        SerdeError::Custom(msg.to_string())
+    }
+}
+
+impl de::Error for SerdeError {
+    fn custom<T: Display>(msg: T) -> Self {
+        SerdeError::Custom(msg.to_string())
+    }
+}
+
+/// Encode value through its `Serialize` impl, as `Sink` encodes a single value
+///
+/// Serde serializes arrays and tuples alike, so the ABI type of the value is
+/// traced the same way as for `from_bytes` to tell `[u8; N]` from `(u8, ...)`.
+pub fn to_bytes<T: Serialize + DeserializeOwned>(value: &T) -> Result<Vec<u8>, SerdeError> {
+    let param = param_type::<T>()?;
+    let value = value.serialize(ValueSerializer)?.into_value();
+    Ok(encode_values(vec![conform(value, &param)]))
+}
+
+/// Reshape arrays and tuples of the serialized value after the traced type
+fn conform(value: AbiValue, param: &ParamType) -> AbiValue {
+    match (value, param) {
+        (AbiValue::Array(members), ParamType::Array(elem)) => AbiValue::Array(
+            members
+                .into_iter()
+                .map(|member| conform(member, elem))
+                .collect(),
+        ),
+        (AbiValue::FixedArray(members), ParamType::FixedBytes(_)) => AbiValue::FixedBytes(
+            members
+                .into_iter()
+                .map(|member| match member {
+                    AbiValue::Uint(byte) => byte.low_u32() as u8,
+                    _ => unreachable!("arrays of bytes are traced from u8 members"),
+                })
+                .collect(),
+        ),
+        (AbiValue::FixedArray(members), ParamType::FixedArray(elem, _)) => AbiValue::FixedArray(
+            members
+                .into_iter()
+                .map(|member| conform(member, elem))
+                .collect(),
+        ),
+        (AbiValue::FixedArray(members), ParamType::Tuple(params))
+        | (AbiValue::Tuple(members), ParamType::Tuple(params)) => AbiValue::Tuple(
+            members
+                .into_iter()
+                .zip(params)
+                .map(|(member, param)| conform(member, param))
+                .collect(),
+        ),
+        (value, _) => value,
+    }
+}
+
+/// Decode value through its `Deserialize` impl, as `Stream` decodes a single value
+///
+/// The ABI type of the value is traced first by deserializing it from zeroes,
+/// empty strings and one-member sequences, so `Deserialize` impls rejecting
+/// those are not supported.
+pub fn from_bytes<T: DeserializeOwned>(payload: &[u8]) -> Result<T, SerdeError> {
+    let param = param_type::<T>()?;
+    let mut stream = Stream::new(payload);
+    let value = stream
+        .pop_value(&param)
+        .map_err(|kind| SerdeError::Decode(stream.error(kind).argument(0, &param.to_string())))?;
+    T::deserialize(ValueDeserializer(value))
+}
+
+/// Serialized value, with `u8` kept apart to encode sequences of them as bytes
+enum Serialized {
+    Value(AbiValue),
+    Byte(u8),
+}
+
+impl Serialized {
+    fn into_value(self) -> AbiValue {
+        match self {
+            Serialized::Value(value) => value,
+            Serialized::Byte(byte) => AbiValue::Uint(U256::from(byte)),
+        }
+    }
+}
+
+struct ValueSerializer;
+
+macro_rules! serialize_unsupported {
+    ($($method: ident($($arg: ty),*) => $what: expr,)*) => {
+        $(
+            fn $method(self, $(_: $arg),*) -> Result<Serialized, SerdeError> {
+                Err(SerdeError::Unsupported($what))
+            }
+        )*
+    };
+}
+
+impl ser::Serializer for ValueSerializer {
+    type Ok = Serialized;
+    type Error = SerdeError;
+    type SerializeSeq = SeqSerializer;
+    type SerializeTuple = SeqSerializer;
+    type SerializeTupleStruct = SeqSerializer;
+    type SerializeTupleVariant = Impossible<Serialized, SerdeError>;
+    type SerializeMap = Impossible<Serialized, SerdeError>;
+    type SerializeStruct = SeqSerializer;
+    type SerializeStructVariant = Impossible<Serialized, SerdeError>;
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+
+    fn serialize_bool(self, v: bool) -> Result<Serialized, SerdeError> {
+        Ok(Serialized::Value(AbiValue::Bool(v)))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Serialized, SerdeError> {
+        self.serialize_i128(v as i128)
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Serialized, SerdeError> {
+        self.serialize_i128(v as i128)
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Serialized, SerdeError> {
+        self.serialize_i128(v as i128)
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Serialized, SerdeError> {
+        self.serialize_i128(v as i128)
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<Serialized, SerdeError> {
+        Ok(Serialized::Value(AbiValue::Int(I256::from(v))))
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Serialized, SerdeError> {
+        Ok(Serialized::Byte(v))
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Serialized, SerdeError> {
+        self.serialize_u128(v as u128)
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Serialized, SerdeError> {
+        self.serialize_u128(v as u128)
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Serialized, SerdeError> {
+        self.serialize_u128(v as u128)
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<Serialized, SerdeError> {
+        Ok(Serialized::Value(AbiValue::Uint(u256_from_u128(v))))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Serialized, SerdeError> {
+        Ok(Serialized::Value(AbiValue::String(v.to_owned())))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Serialized, SerdeError> {
+        Ok(Serialized::Value(AbiValue::Bytes(v.to_vec())))
+    }
+
+    serialize_unsupported! {
+        serialize_f32(f32) => "floating point numbers",
+        serialize_f64(f64) => "floating point numbers",
+        serialize_char(char) => "chars",
+        serialize_none() => "options",
+        serialize_unit() => "units",
+        serialize_unit_struct(&'static str) => "units",
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, _: &T) -> Result<Serialized, SerdeError> {
+        Err(SerdeError::Unsupported("options"))
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        index: u32,
+        _variant: &'static str,
+    ) -> Result<Serialized, SerdeError> {
+        Ok(Serialized::Value(AbiValue::Uint(U256::from(index))))
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<Serialized, SerdeError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<Serialized, SerdeError> {
+        Err(SerdeError::Unsupported("enum variants with data"))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<SeqSerializer, SerdeError> {
+        Ok(SeqSerializer::new(len.unwrap_or(0), SeqKind::Array))
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<SeqSerializer, SerdeError> {
+        Ok(SeqSerializer::new(len, SeqKind::FixedArray))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<SeqSerializer, SerdeError> {
+        Ok(SeqSerializer::new(len, SeqKind::Tuple))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Impossible<Serialized, SerdeError>, SerdeError> {
+        Err(SerdeError::Unsupported("enum variants with data"))
+    }
+
+    fn serialize_map(
+        self,
+        _len: Option<usize>,
+    ) -> Result<Impossible<Serialized, SerdeError>, SerdeError> {
+        Err(SerdeError::Unsupported("maps"))
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<SeqSerializer, SerdeError> {
+        Ok(SeqSerializer::new(len, SeqKind::Tuple))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Impossible<Serialized, SerdeError>, SerdeError> {
+        Err(SerdeError::Unsupported("enum variants with data"))
+    }
+}
+
+enum SeqKind {
+    /// Sequence, `T[]` or `bytes` for `u8` members
+    Array,
+    /// Tuple or array, told apart by `conform` as serde serializes them alike
+    FixedArray,
+    /// Struct, always `(T1,T2,...)`
+    Tuple,
+}
+
+struct SeqSerializer {
+    members: Vec<Serialized>,
+    kind: SeqKind,
+}
+
+impl SeqSerializer {
+    fn new(len: usize, kind: SeqKind) -> Self {
+        SeqSerializer {
+            members: Vec::with_capacity(len),
+            kind,
+        }
+    }
+
+    fn push<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SerdeError> {
+        self.members.push(value.serialize(ValueSerializer)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Serialized, SerdeError> {
+        let is_bytes = match self.kind {
+            SeqKind::Array => {
+                !self.members.is_empty()
+                    && self.members.iter().all(|member| match *member {
+                        Serialized::Byte(_) => true,
+                        Serialized::Value(_) => false,
+                    })
+            }
+            _ => false,
+        };
+        if is_bytes {
+            let bytes = self.members.into_iter().map(|member| match member {
+                Serialized::Byte(byte) => byte,
+                Serialized::Value(_) => unreachable!("checked above"),
+            });
+            return Ok(Serialized::Value(AbiValue::Bytes(bytes.collect())));
+        }
+
+        let members = self.members.into_iter().map(Serialized::into_value).collect();
+        Ok(Serialized::Value(match self.kind {
+            SeqKind::Array => AbiValue::Array(members),
+            SeqKind::FixedArray => AbiValue::FixedArray(members),
+            SeqKind::Tuple => AbiValue::Tuple(members),
+        }))
+    }
+}
+
+impl ser::SerializeSeq for SeqSerializer {
+    type Ok = Serialized;
+    type Error = SerdeError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SerdeError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Serialized, SerdeError> {
+        SeqSerializer::end(self)
+    }
+}
+
+impl ser::SerializeTuple for SeqSerializer {
+    type Ok = Serialized;
+    type Error = SerdeError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SerdeError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Serialized, SerdeError> {
+        SeqSerializer::end(self)
+    }
+}
+
+impl ser::SerializeTupleStruct for SeqSerializer {
+    type Ok = Serialized;
+    type Error = SerdeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SerdeError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Serialized, SerdeError> {
+        SeqSerializer::end(self)
+    }
+}
+
+impl ser::SerializeStruct for SeqSerializer {
+    type Ok = Serialized;
+    type Error = SerdeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        _key: &'static str,
+        value: &T,
+    ) -> Result<(), SerdeError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Serialized, SerdeError> {
+        SeqSerializer::end(self)
+    }
+}
+
+/// Trace ABI type of `T` by deserializing it from placeholder values
+fn param_type<T: DeserializeOwned>() -> Result<ParamType, SerdeError> {
+    let mut shape = Vec::new();
+    T::deserialize(Probe { shape: &mut shape })?;
+    Ok(shape.pop().expect("every probe pushes its type").into_param())
+}
+
+/// Traced type, with `u8` kept apart to decode sequences of them as bytes
+enum Probed {
+    Param(ParamType),
+    Byte,
+}
+
+impl Probed {
+    fn into_param(self) -> ParamType {
+        match self {
+            Probed::Param(param) => param,
+            Probed::Byte => ParamType::Uint(8),
+        }
+    }
+
+    fn is_byte(&self) -> bool {
+        match *self {
+            Probed::Byte => true,
+            Probed::Param(_) => false,
+        }
+    }
+}
+
+/// Deserializer of placeholder values, recording the type requested
+struct Probe<'a> {
+    shape: &'a mut Vec<Probed>,
+}
+
+impl<'a> Probe<'a> {
+    /// Deserialize `len` members, pushing the type of the sequence built from their types
+    fn members<'de, V, F>(self, len: usize, visitor: V, param: F) -> Result<V::Value, SerdeError>
+    where
+        V: Visitor<'de>,
+        F: FnOnce(Vec<Probed>) -> ParamType,
+    {
+        let mut members = ProbeMembers {
+            shape: Vec::with_capacity(len),
+            remaining: len,
+        };
+        let value = visitor.visit_seq(&mut members)?;
+        self.shape.push(Probed::Param(param(members.shape)));
+        Ok(value)
+    }
+}
+
+macro_rules! probe_int {
+    ($($method: ident => $visit: ident, $param: expr,)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+                self.shape.push(Probed::Param($param));
+                visitor.$visit(0)
+            }
+        )*
+    };
+}
+
+macro_rules! probe_unsupported {
+    ($($method: ident => $what: expr,)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, _: V) -> Result<V::Value, SerdeError> {
+                Err(SerdeError::Unsupported($what))
+            }
+        )*
+    };
+}
+
+impl<'de, 'a> de::Deserializer<'de> for Probe<'a> {
+    type Error = SerdeError;
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+
+    probe_int! {
+        deserialize_i8 => visit_i8, ParamType::Int(8),
+        deserialize_i16 => visit_i16, ParamType::Int(16),
+        deserialize_i32 => visit_i32, ParamType::Int(32),
+        deserialize_i64 => visit_i64, ParamType::Int(64),
+        deserialize_i128 => visit_i128, ParamType::Int(128),
+        deserialize_u16 => visit_u16, ParamType::Uint(16),
+        deserialize_u32 => visit_u32, ParamType::Uint(32),
+        deserialize_u64 => visit_u64, ParamType::Uint(64),
+        deserialize_u128 => visit_u128, ParamType::Uint(128),
+    }
+
+    probe_unsupported! {
+        deserialize_any => "self-describing types",
+        deserialize_f32 => "floating point numbers",
+        deserialize_f64 => "floating point numbers",
+        deserialize_char => "chars",
+        deserialize_option => "options",
+        deserialize_unit => "units",
+        deserialize_map => "maps",
+        deserialize_identifier => "identifiers",
+        deserialize_ignored_any => "ignored values",
+    }
+
+    fn deserialize_u8<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        self.shape.push(Probed::Byte);
+        visitor.visit_u8(0)
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        self.shape.push(Probed::Param(ParamType::Bool));
+        visitor.visit_bool(false)
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        self.shape.push(Probed::Param(ParamType::String));
+        visitor.visit_str("")
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        self.shape.push(Probed::Param(ParamType::Bytes));
+        visitor.visit_bytes(&[])
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _visitor: V,
+    ) -> Result<V::Value, SerdeError> {
+        Err(SerdeError::Unsupported("units"))
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, SerdeError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        // a single member is enough to learn the type of all of them
+        self.members(1, visitor, |mut members| match members.pop() {
+            Some(Probed::Byte) => ParamType::Bytes,
+            Some(member) => ParamType::Array(Box::new(member.into_param())),
+            None => ParamType::Array(Box::new(ParamType::Tuple(Vec::new()))),
+        })
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, SerdeError> {
+        if !visits_array(&visitor) {
+            return self.members(len, visitor, tuple);
+        }
+        self.members(len, visitor, |mut members| {
+            if len >= 1 && len <= 32 && members.iter().all(Probed::is_byte) {
+                ParamType::FixedBytes(len)
+            } else {
+                let elem = match members.pop() {
+                    Some(member) => member.into_param(),
+                    None => ParamType::Tuple(Vec::new()),
+                };
+                ParamType::FixedArray(Box::new(elem), len)
+            }
+        })
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, SerdeError> {
+        self.members(len, visitor, tuple)
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, SerdeError> {
+        self.members(fields.len(), visitor, tuple)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, SerdeError> {
+        self.shape.push(Probed::Param(ParamType::Uint(8)));
+        visitor.visit_enum(IntoDeserializer::<SerdeError>::into_deserializer(0u32))
+    }
+}
+
+/// Whether the visitor is the one of `[T; N]`, as serde deserializes arrays and
+/// tuples alike and only tells them apart in what the visitor expects
+fn visits_array<'de, V: Visitor<'de>>(visitor: &V) -> bool {
+    (visitor as &dyn de::Expected).to_string().contains("array")
+}
+
+fn tuple(members: Vec<Probed>) -> ParamType {
+    ParamType::Tuple(members.into_iter().map(Probed::into_param).collect())
+}
+
+struct ProbeMembers {
+    shape: Vec<Probed>,
+    remaining: usize,
+}
+
+impl<'de> de::SeqAccess<'de> for ProbeMembers {
+    type Error = SerdeError;
+
+    fn next_element_seed<S>(&mut self, seed: S) -> Result<Option<S::Value>, SerdeError>
+    where
+        S: de::DeserializeSeed<'de>,
+    {
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+        self.remaining -= 1;
+        seed.deserialize(Probe {
+            shape: &mut self.shape,
+        })
+        .map(Some)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.remaining)
+    }
+}
+
+/// Deserializer of the decoded value
+struct ValueDeserializer(AbiValue);
+
+impl<'de> IntoDeserializer<'de, SerdeError> for ValueDeserializer {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+fn visit_members<'de, I, V>(members: I, visitor: V) -> Result<V::Value, SerdeError>
+where
+    I: Iterator,
+    I::Item: IntoDeserializer<'de, SerdeError>,
+    V: Visitor<'de>,
+{
+    let mut members = SeqDeserializer::new(members);
+    let value = visitor.visit_seq(&mut members)?;
+    members.end()?;
+    Ok(value)
+}
+
+impl<'de> de::Deserializer<'de> for ValueDeserializer {
+    type Error = SerdeError;
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        match self.0 {
+            AbiValue::Uint(value) => {
+                if fits_unsigned(&value, 64) {
+                    visitor.visit_u64(value.low_u64())
+                } else if fits_unsigned(&value, 128) {
+                    visitor.visit_u128(u256_low_u128(&value))
+                } else {
+                    Err(SerdeError::Unsupported("integers wider than 128 bits"))
+                }
+            }
+            AbiValue::Int(value) => match convert::TryFrom::try_from(value) {
+                Ok(value) if value as i64 as i128 == value => visitor.visit_i64(value as i64),
+                Ok(value) => visitor.visit_i128(value),
+                Err(_) => Err(SerdeError::Unsupported("integers wider than 128 bits")),
+            },
+            AbiValue::Bool(value) => visitor.visit_bool(value),
+            AbiValue::Bytes(bytes) | AbiValue::FixedBytes(bytes) => visitor.visit_byte_buf(bytes),
+            AbiValue::String(value) => visitor.visit_string(value),
+            AbiValue::Address(_) => Err(SerdeError::Unsupported("addresses")),
+            AbiValue::Array(members) | AbiValue::FixedArray(members) | AbiValue::Tuple(members) => {
+                visit_members(members.into_iter().map(ValueDeserializer), visitor)
+            }
+        }
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        match self.0 {
+            AbiValue::Bytes(bytes) | AbiValue::FixedBytes(bytes) => {
+                visit_members(bytes.into_iter(), visitor)
+            }
+            value => ValueDeserializer(value).deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, SerdeError> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, SerdeError> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, SerdeError> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, SerdeError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, SerdeError> {
+        match self.0 {
+            AbiValue::Uint(index) if fits_unsigned(&index, 32) => visitor.visit_enum(
+                IntoDeserializer::<SerdeError>::into_deserializer(index.low_u64() as u32),
+            ),
+            value => ValueDeserializer(value).deserialize_any(visitor),
+        }
+    }
+
+    ::serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct map identifier ignored_any
+    }
+}
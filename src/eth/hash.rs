@@ -0,0 +1,185 @@
+//! Keccak hashes of signatures, for selectors and event topics
+
+use super::{types::H256, util};
+
+const RATE: usize = 136;
+
+const ROUND_CONSTANTS: [u64; 24] = [
+    0x0000_0000_0000_0001,
+    0x0000_0000_0000_8082,
+    0x8000_0000_0000_808a,
+    0x8000_0000_8000_8000,
+    0x0000_0000_0000_808b,
+    0x0000_0000_8000_0001,
+    0x8000_0000_8000_8081,
+    0x8000_0000_0000_8009,
+    0x0000_0000_0000_008a,
+    0x0000_0000_0000_0088,
+    0x0000_0000_8000_8009,
+    0x0000_0000_8000_000a,
+    0x0000_0000_8000_808b,
+    0x8000_0000_0000_008b,
+    0x8000_0000_0000_8089,
+    0x8000_0000_0000_8003,
+    0x8000_0000_0000_8002,
+    0x8000_0000_0000_0080,
+    0x0000_0000_0000_800a,
+    0x8000_0000_8000_000a,
+    0x8000_0000_8000_8081,
+    0x8000_0000_0000_8080,
+    0x0000_0000_8000_0001,
+    0x8000_0000_8000_8008,
+];
+
+const ROTATIONS: [u32; 24] = [
+    1, 3, 6, 10, 15, 21, 28, 36, 45, 55, 2, 14, 27, 41, 56, 8, 25, 43, 62, 18, 39, 61, 20, 44,
+];
+
+const LANES: [usize; 24] = [
+    10, 7, 11, 17, 18, 3, 5, 16, 8, 21, 24, 4, 15, 23, 19, 13, 12, 2, 20, 14, 22, 9, 6, 1,
+];
+
+/// Keccak-256 hash of the given bytes
+pub fn keccak256(bytes: &[u8]) -> H256 {
+    util::keccak(bytes)
+}
+
+/// Keccak-256 hash of the given bytes, usable in constants
+///
+/// Gives the same result as `keccak256`, which should be preferred at runtime.
+pub const fn const_keccak256(bytes: &[u8]) -> [u8; 32] {
+    Sponge::new().absorb(bytes).finish()
+}
+
+/// Keccak-256 sponge absorbing its input piece by piece, usable in constants
+///
+/// Lets canonical signatures be hashed while walking their types,
+/// without building the string first.
+#[derive(Clone, Copy)]
+pub(crate) struct Sponge {
+    state: [u64; 25],
+    pos: usize,
+}
+
+impl Sponge {
+    pub(crate) const fn new() -> Self {
+        Sponge {
+            state: [0u64; 25],
+            pos: 0,
+        }
+    }
+
+    pub(crate) const fn absorb_byte(mut self, byte: u8) -> Self {
+        self.state[self.pos / 8] ^= (byte as u64) << (8 * (self.pos % 8));
+        self.pos += 1;
+        if self.pos == RATE {
+            self.state = keccak_f(self.state);
+            self.pos = 0;
+        }
+        self
+    }
+
+    pub(crate) const fn absorb(mut self, bytes: &[u8]) -> Self {
+        let mut i = 0;
+        while i < bytes.len() {
+            self = self.absorb_byte(bytes[i]);
+            i += 1;
+        }
+        self
+    }
+
+    pub(crate) const fn finish(mut self) -> [u8; 32] {
+        self.state[self.pos / 8] ^= 0x01 << (8 * (self.pos % 8));
+        self.state[(RATE - 1) / 8] ^= 0x80 << (8 * ((RATE - 1) % 8));
+        self.state = keccak_f(self.state);
+
+        let mut result = [0u8; 32];
+        let mut i = 0;
+        while i < 32 {
+            result[i] = (self.state[i / 8] >> (8 * (i % 8))) as u8;
+            i += 1;
+        }
+        result
+    }
+}
+
+/// Function selector: first 4 bytes of the Keccak hash of the signature
+///
+/// Signature is hashed as is, so it has to be canonical, like
+/// `transfer(address,uint256)`. Use `Signature::parse` to canonicalize it first
+/// when it comes from user input.
+///
+/// ```
+/// # use owasm_abi::eth::selector;
+/// const TRANSFER: u32 = selector("transfer(address,uint256)");
+/// assert_eq!(TRANSFER, 0xa9059cbb);
+/// ```
+pub const fn selector(signature: &str) -> u32 {
+    let hash = const_keccak256(signature.as_bytes());
+    u32::from_be_bytes([hash[0], hash[1], hash[2], hash[3]])
+}
+
+/// Event topic: Keccak hash of the signature, used as the first topic of the log
+///
+/// Signature has to be canonical, the same way as for `selector`.
+///
+/// ```
+/// # use owasm_abi::eth::event_topic;
+/// const TRANSFER: [u8; 32] = event_topic("Transfer(address,address,uint256)");
+/// assert_eq!(TRANSFER[..4], [0xdd, 0xf2, 0x52, 0xad]);
+/// ```
+pub const fn event_topic(signature: &str) -> [u8; 32] {
+    const_keccak256(signature.as_bytes())
+}
+
+/// Keccak-f[1600] permutation
+const fn keccak_f(mut state: [u64; 25]) -> [u64; 25] {
+    let mut round = 0;
+    while round < 24 {
+        // theta
+        let mut columns = [0u64; 5];
+        let mut x = 0;
+        while x < 5 {
+            columns[x] = state[x] ^ state[x + 5] ^ state[x + 10] ^ state[x + 15] ^ state[x + 20];
+            x += 1;
+        }
+        let mut x = 0;
+        while x < 5 {
+            let d = columns[(x + 4) % 5] ^ columns[(x + 1) % 5].rotate_left(1);
+            let mut y = 0;
+            while y < 25 {
+                state[y + x] ^= d;
+                y += 5;
+            }
+            x += 1;
+        }
+
+        // rho and pi
+        let mut last = state[1];
+        let mut i = 0;
+        while i < 24 {
+            let lane = LANES[i];
+            let next = state[lane];
+            state[lane] = last.rotate_left(ROTATIONS[i]);
+            last = next;
+            i += 1;
+        }
+
+        // chi
+        let mut y = 0;
+        while y < 25 {
+            let row = [state[y], state[y + 1], state[y + 2], state[y + 3], state[y + 4]];
+            let mut x = 0;
+            while x < 5 {
+                state[y + x] = row[x] ^ (!row[(x + 1) % 5] & row[(x + 2) % 5]);
+                x += 1;
+            }
+            y += 5;
+        }
+
+        // iota
+        state[0] ^= ROUND_CONSTANTS[round];
+        round += 1;
+    }
+    state
+}
@@ -0,0 +1,234 @@
+//! JSON ABI, as read by the Ethereum tooling
+
+use super::Canonical;
+use lib::*;
+
+/// Parameter of an entry of the JSON ABI
+#[derive(Clone, Copy)]
+pub struct JsonParam {
+    /// Name of the parameter
+    pub name: &'static str,
+    /// Canonical type of the parameter, usually `AbiType::CANONICAL`
+    pub canonical: Canonical,
+    /// Whether the parameter is indexed, only listed for events
+    pub indexed: bool,
+}
+
+/// Entry of the JSON ABI, listed with the `type` it's tagged by
+#[derive(Clone, Copy)]
+pub enum JsonEntry {
+    /// `function`
+    Function {
+        /// Name of the function
+        name: &'static str,
+        /// Parameters of the function
+        inputs: &'static [JsonParam],
+        /// Return values of the function
+        outputs: &'static [JsonParam],
+        /// Whether the function doesn't modify the state
+        constant: bool,
+    },
+    /// `event`
+    Event {
+        /// Name of the event
+        name: &'static str,
+        /// Parameters of the event, indexed ones first
+        inputs: &'static [JsonParam],
+    },
+    /// `error`
+    Error {
+        /// Name of the error
+        name: &'static str,
+        /// Parameters of the error
+        inputs: &'static [JsonParam],
+    },
+    /// `constructor`
+    Constructor {
+        /// Parameters of the constructor
+        inputs: &'static [JsonParam],
+    },
+}
+
+/// JSON ABI made of the given entries, pretty-printed
+///
+/// Types are given by their canonical type: structs and other tuples are `tuple`
+/// with their members listed in `components`, enums are `uint8`.
+///
+/// ```
+/// # use owasm_abi::eth::{json_abi, AbiType, JsonEntry, JsonParam};
+/// # use owasm_abi::types::*;
+/// let abi = json_abi(&[JsonEntry::Event {
+///     name: "Paid",
+///     inputs: &[JsonParam {
+///         name: "payment",
+///         canonical: <(U256, Address) as AbiType>::CANONICAL,
+///         indexed: false,
+///     }],
+/// }]);
+/// assert!(abi.contains(r#""type": "tuple""#));
+/// assert!(abi.contains(r#""type": "address""#));
+/// ```
+pub fn json_abi(entries: &[JsonEntry]) -> String {
+    let mut json = Json {
+        out: String::new(),
+        depth: 0,
+        first: true,
+    };
+    json.open('[');
+    for entry in entries {
+        json.item();
+        json.open('{');
+        match *entry {
+            JsonEntry::Function {
+                name,
+                inputs,
+                outputs,
+                constant,
+            } => {
+                json.field("type", "function");
+                json.field("name", name);
+                json.params("inputs", inputs, false);
+                json.params("outputs", outputs, false);
+                json.key("constant");
+                json.out.push_str(if constant { "true" } else { "false" });
+            }
+            JsonEntry::Event { name, inputs } => {
+                json.field("type", "event");
+                json.field("name", name);
+                json.params("inputs", inputs, true);
+            }
+            JsonEntry::Error { name, inputs } => {
+                json.field("type", "error");
+                json.field("name", name);
+                json.params("inputs", inputs, false);
+            }
+            JsonEntry::Constructor { inputs } => {
+                json.field("type", "constructor");
+                json.params("inputs", inputs, false);
+            }
+        }
+        json.close('}');
+    }
+    json.close(']');
+    json.out
+}
+
+/// Writer of pretty-printed JSON, indented by two spaces
+struct Json {
+    out: String,
+    depth: usize,
+    // whether nothing was written to the current object or array yet
+    first: bool,
+}
+
+impl Json {
+    fn open(&mut self, bracket: char) {
+        self.out.push(bracket);
+        self.depth += 1;
+        self.first = true;
+    }
+
+    fn close(&mut self, bracket: char) {
+        self.depth -= 1;
+        if !self.first {
+            self.newline();
+        }
+        self.out.push(bracket);
+        self.first = false;
+    }
+
+    fn item(&mut self) {
+        if !self.first {
+            self.out.push(',');
+        }
+        self.newline();
+        self.first = false;
+    }
+
+    fn newline(&mut self) {
+        self.out.push('\n');
+        for _ in 0..self.depth {
+            self.out.push_str("  ");
+        }
+    }
+
+    fn key(&mut self, key: &str) {
+        self.item();
+        self.string(key);
+        self.out.push_str(": ");
+    }
+
+    fn string(&mut self, value: &str) {
+        self.out.push('"');
+        for c in value.chars() {
+            match c {
+                '"' | '\\' => {
+                    self.out.push('\\');
+                    self.out.push(c);
+                }
+                _ => self.out.push(c),
+            }
+        }
+        self.out.push('"');
+    }
+
+    fn field(&mut self, key: &str, value: &str) {
+        self.key(key);
+        self.string(value);
+    }
+
+    fn params(&mut self, key: &str, params: &[JsonParam], with_indexed: bool) {
+        self.key(key);
+        self.open('[');
+        for param in params {
+            let indexed = if with_indexed { Some(param.indexed) } else { None };
+            self.param(param.name, &param.canonical, indexed);
+        }
+        self.close(']');
+    }
+
+    fn param(&mut self, name: &str, canonical: &Canonical, indexed: Option<bool>) {
+        let (type_, tuple) = json_type(canonical);
+        self.item();
+        self.open('{');
+        self.field("name", name);
+        self.field("type", &type_);
+        if let Some((members, names)) = tuple {
+            self.key("components");
+            self.open('[');
+            for (i, member) in members.iter().enumerate() {
+                self.param(names.get(i).cloned().unwrap_or(""), member, None);
+            }
+            self.close(']');
+        }
+        if let Some(indexed) = indexed {
+            self.key("indexed");
+            self.out.push_str(if indexed { "true" } else { "false" });
+        }
+        self.close('}');
+    }
+}
+
+type Members = (&'static [Canonical], &'static [&'static str]);
+
+/// Type of the parameter in the JSON ABI, with the members of the tuple it's made of, if any
+///
+/// Tuples are `tuple` and arrays of them `tuple[]` or `tuple[N]`, any other type
+/// is named by its canonical string.
+fn json_type(canonical: &Canonical) -> (String, Option<Members>) {
+    match *canonical {
+        Canonical::Tuple { members, names } => ("tuple".to_owned(), Some((members, names))),
+        Canonical::Array {
+            elem,
+            is_byte: false,
+        } => {
+            let (type_, tuple) = json_type(elem);
+            (type_ + "[]", tuple)
+        }
+        Canonical::FixedArray { elem, len, is_byte } if !(is_byte && len >= 1 && len <= 32) => {
+            let (type_, tuple) = json_type(elem);
+            (format!("{}[{}]", type_, len), tuple)
+        }
+        _ => (canonical.to_string(), None),
+    }
+}
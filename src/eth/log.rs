@@ -1,10 +1,15 @@
 //! Log module
 
-use super::types::*;
+use super::{common::is_fixed_bytes, types::*, util, AbiType, EncodeInto};
 use byteorder::{BigEndian, ByteOrder};
+use lib::*;
 
 /// As log trait for how primitive types are represented as indexed arguments
 /// of the event log
+///
+/// Static types take their encoded word, `bytesN` are left-aligned, while
+/// `bytes`, `string` and arrays are represented by the Keccak hash of their
+/// encoding, as Solidity does.
 pub trait AsLog {
     /// Convert type to hash representation for the event log.
     fn as_log(&self) -> H256;
@@ -42,10 +47,37 @@ impl AsLog for i32 {
     }
 }
 
+macro_rules! as_log_unsigned_impl {
+    ($t: ty) => {
+        impl AsLog for $t {
+            fn as_log(&self) -> H256 {
+                util::pad_unsigned(&self.to_be_bytes()).into()
+            }
+        }
+    };
+}
+
+macro_rules! as_log_signed_impl {
+    ($t: ty) => {
+        impl AsLog for $t {
+            fn as_log(&self) -> H256 {
+                util::pad_signed(&self.to_be_bytes()).into()
+            }
+        }
+    };
+}
+
+as_log_unsigned_impl!(u8);
+as_log_unsigned_impl!(u16);
+as_log_unsigned_impl!(u128);
+as_log_signed_impl!(i8);
+as_log_signed_impl!(i16);
+as_log_signed_impl!(i128);
+
 impl AsLog for bool {
     fn as_log(&self) -> H256 {
         let mut result = H256::zero();
-        result.as_mut()[32] = if *self { 1 } else { 0 };
+        result.as_mut()[31] = if *self { 1 } else { 0 };
         result
     }
 }
@@ -58,6 +90,24 @@ impl AsLog for U256 {
     }
 }
 
+impl AsLog for I256 {
+    fn as_log(&self) -> H256 {
+        self.into_raw().as_log()
+    }
+}
+
+impl<const BITS: usize> AsLog for Uint<BITS> {
+    fn as_log(&self) -> H256 {
+        self.value().as_log()
+    }
+}
+
+impl<const BITS: usize> AsLog for Int<BITS> {
+    fn as_log(&self) -> H256 {
+        self.value().as_log()
+    }
+}
+
 impl AsLog for H256 {
     fn as_log(&self) -> H256 {
         self.clone()
@@ -69,3 +119,54 @@ impl AsLog for Address {
         self.into()
     }
 }
+
+impl AsLog for String {
+    fn as_log(&self) -> H256 {
+        self.as_str().as_log()
+    }
+}
+
+impl AsLog for str {
+    fn as_log(&self) -> H256 {
+        util::keccak(self.as_bytes())
+    }
+}
+
+impl<'a, T: AsLog + ?Sized> AsLog for &'a T {
+    fn as_log(&self) -> H256 {
+        (**self).as_log()
+    }
+}
+
+impl AsLog for [u8] {
+    fn as_log(&self) -> H256 {
+        util::keccak(self)
+    }
+}
+
+/// Indexed arrays are represented by the hash of their members encoded in place,
+/// i.e. padded to 32 bytes each without the length, members of `bytes` are hashed as is.
+impl<T: EncodeInto> AsLog for Vec<T> {
+    fn as_log(&self) -> H256 {
+        let mut encoded = Vec::new();
+        self.encode_in_place(&mut encoded);
+        if T::Owned::IS_BYTE {
+            encoded.truncate(self.len());
+        }
+        util::keccak(&encoded)
+    }
+}
+
+impl<T: EncodeInto, const N: usize> AsLog for [T; N] {
+    fn as_log(&self) -> H256 {
+        let mut encoded = Vec::new();
+        self.encode_in_place(&mut encoded);
+        if is_fixed_bytes::<T::Owned>(N) {
+            // `bytesN` is a static type, left-aligned like in its encoding
+            H256::from_slice(&encoded)
+        } else {
+            // longer `[u8; N]` are `uint8[N]`, hashed with one word per member
+            util::keccak(&encoded)
+        }
+    }
+}
@@ -2,20 +2,53 @@
 
 #![warn(missing_docs)]
 
+#[cfg(feature = "std")]
+mod annotate;
+mod buffer;
+mod canonical;
 mod common;
+#[cfg(feature = "serde")]
+mod format;
+mod hash;
+mod json;
 mod log;
+mod packed;
+mod revert;
+mod signature;
 mod sink;
 mod stream;
 #[cfg(test)]
 mod tests;
 mod util;
+mod value;
 
-pub use self::{log::AsLog, sink::Sink, stream::Stream};
+#[cfg(feature = "std")]
+pub use self::annotate::annotate;
+pub use self::{
+    buffer::{encode_into, BufferOverflow, BufferSink, EncodeInto},
+    canonical::Canonical,
+    json::{json_abi, JsonEntry, JsonParam},
+    log::AsLog,
+    packed::{encode_packed, PackedAbiType, PackedMember, PackedSink},
+    revert::{
+        decode_revert, encode_error, encode_panic, ContractError, Revert, RevertReason,
+        ERROR_SELECTOR, PANIC_SELECTOR,
+    },
+    signature::{Signature, SignatureError},
+    sink::{CapacityMismatch, Sink},
+    stream::Stream,
+    value::{decode_values, encode_values, AbiValue, ParamType},
+};
+
+#[cfg(feature = "serde")]
+pub use self::format::{from_bytes, to_bytes, SerdeError};
+
+pub use self::hash::{const_keccak256, event_topic, keccak256, selector};
 
 use super::types;
 
 /// Error for decoding rust types from stream
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Error {
     /// Invalid bool for provided input
     InvalidBool,
@@ -25,12 +58,97 @@ pub enum Error {
     InvalidU64,
     /// Unexpected end of the stream
     UnexpectedEof,
-    /// Invalid padding for fixed type
+    /// Invalid padding for fixed type, or for byte sequences in strict mode
     InvalidPadding,
+    /// Invalid utf-8 sequence for string
+    InvalidUtf8,
+    /// Integer does not fit into its declared bit width
+    IntegerOverflow,
+    /// Invalid enum variant for provided input
+    InvalidEnum,
+    /// Offset of dynamic type points outside of the payload
+    InvalidOffset,
+    /// Length of dynamic type exceeds the payload
+    LengthOverflow,
+    /// Offset of dynamic type does not follow the canonical layout (strict mode only)
+    NonCanonicalOffset,
+    /// Payload continues after the encoded data (strict mode only)
+    TrailingBytes,
+    /// Topics of the log do not match the event
+    InvalidTopic,
+    /// Payload does not start with the selector of any expected signature
+    UnknownSelector,
+    /// Type known at runtime is invalid, like `uint7` or `bytes33`
+    InvalidParamType,
     /// Other error
     Other,
 }
 
+impl ::lib::fmt::Display for Error {
+    fn fmt(&self, f: &mut ::lib::fmt::Formatter) -> ::lib::fmt::Result {
+        let description = match *self {
+            Error::InvalidBool => "invalid bool",
+            Error::InvalidU32 => "invalid u32",
+            Error::InvalidU64 => "invalid u64",
+            Error::UnexpectedEof => "unexpected end of payload",
+            Error::InvalidPadding => "invalid padding",
+            Error::InvalidUtf8 => "invalid utf-8 sequence",
+            Error::IntegerOverflow => "integer does not fit into its bit width",
+            Error::InvalidEnum => "invalid enum variant",
+            Error::InvalidOffset => "offset points outside of the payload",
+            Error::LengthOverflow => "length exceeds the payload",
+            Error::NonCanonicalOffset => "non-canonical offset",
+            Error::TrailingBytes => "trailing bytes after the encoded data",
+            Error::InvalidTopic => "topics do not match the event",
+            Error::UnknownSelector => "unknown selector",
+            Error::InvalidParamType => "invalid parameter type",
+            Error::Other => "decoding failed",
+        };
+        f.write_str(description)
+    }
+}
+
+#[cfg(feature = "std")]
+impl ::std::error::Error for Error {}
+
+/// Error for decoding rust types from stream, with the context it happened in
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodeError {
+    /// What went wrong
+    pub kind: Error,
+    /// Offset in the payload of the value which failed to decode
+    pub offset: usize,
+    /// Index of the argument which failed to decode, if known
+    pub argument: Option<usize>,
+    /// ABI type of the argument which failed to decode, if known
+    pub expected: Option<::lib::String>,
+}
+
+impl DecodeError {
+    /// Attach index and ABI type of the argument which failed to decode
+    pub fn argument<E: ::lib::Display + ?Sized>(mut self, index: usize, expected: &E) -> Self {
+        self.argument = Some(index);
+        self.expected = Some(::lib::ToString::to_string(expected));
+        self
+    }
+}
+
+impl ::lib::fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut ::lib::fmt::Formatter) -> ::lib::fmt::Result {
+        write!(f, "{} at offset {}", self.kind, self.offset)?;
+        if let Some(index) = self.argument {
+            write!(f, " in argument {}", index)?;
+        }
+        if let Some(ref expected) = self.expected {
+            write!(f, " of type {}", expected)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl ::std::error::Error for DecodeError {}
+
 /// Abi type trait
 pub trait AbiType: Sized {
     /// Insantiate type from data stream
@@ -43,13 +161,99 @@ pub trait AbiType: Sized {
 
     /// Whether type has fixed length or not
     const IS_FIXED: bool;
+
+    /// Canonical type, as it appears in signatures
+    const CANONICAL: Canonical;
+
+    /// Number of 32-byte words the type occupies in the head of the encoding
+    /// Dynamic types are always referenced from the head by a single offset word
+    const HEAD_WORDS: usize = 1;
+
+    /// Whether arrays of the type are packed as `bytes`/`bytesN` instead of one word per member
+    #[doc(hidden)]
+    const IS_BYTE: bool = false;
+
+    /// Instantiate `len` consecutive members of an array from data stream
+    /// Should never be called manually! Used by `Vec<T>` and `[T; N]`
+    #[doc(hidden)]
+    fn decode_members(stream: &mut Stream, len: usize) -> Result<::lib::Vec<Self>, Error> {
+        stream.check_members(len, Self::HEAD_WORDS)?;
+        stream.pop_members(|stream| {
+            let mut result = ::lib::Vec::with_capacity(len);
+            for _ in 0..len {
+                result.push(stream.pop()?);
+            }
+            Ok(result)
+        })
+    }
+
+    /// Push consecutive members of an array to data sink
+    /// Should never be called manually! Used by `Vec<T>` and `[T; N]`
+    #[doc(hidden)]
+    fn encode_members(members: ::lib::Vec<Self>, sink: &mut Sink) {
+        if Self::IS_FIXED {
+            for member in members.into_iter() {
+                sink.push(member);
+            }
+        } else {
+            // offsets of dynamic members are relative to the first member
+            let mut nested_sink = Sink::new(members.len());
+            for member in members.into_iter() {
+                nested_sink.push(member);
+            }
+            nested_sink.drain_to(sink.preamble_mut());
+        }
+    }
+}
+
+/// Abi type which borrows its decoded value from the payload of the stream
+///
+/// Allows dynamic `bytes` and `string` to be decoded as `&[u8]` and `&str`
+/// without copying them out of the payload.
+pub trait BorrowedAbiType<'a>: Sized {
+    /// Instantiate type from data stream
+    /// Should never be called manually! Use stream.pop_borrowed()
+    fn decode(stream: &mut Stream<'a>) -> Result<Self, Error>;
 }
 
 /// Endpoint interface for contracts
+///
+/// Implementors provide `try_dispatch` and `try_dispatch_ctor`, which return the payload
+/// to revert with instead of failing the call themselves, the other methods are built on them.
 pub trait EndpointInterface {
+    /// Dispatch payload for regular method, returning its result or the payload to revert with
+    fn try_dispatch(&mut self, payload: &[u8]) -> Result<::lib::Vec<u8>, Revert>;
+
+    /// Dispatch constructor payload, returning the payload to revert with on failure
+    fn try_dispatch_ctor(&mut self, payload: &[u8]) -> Result<(), Revert>;
+
     /// Dispatch payload for regular method
-    fn dispatch(&mut self, payload: &[u8]) -> ::lib::Vec<u8>;
+    ///
+    /// Fails the call with `Revert::abort` if it reverts.
+    fn dispatch(&mut self, payload: &[u8]) -> ::lib::Vec<u8> {
+        match self.try_dispatch(payload) {
+            Ok(result) => result,
+            Err(revert) => revert.abort(),
+        }
+    }
 
     /// Dispatch constructor payload
-    fn dispatch_ctor(&mut self, payload: &[u8]);
+    ///
+    /// Fails the call with `Revert::abort` if the constructor reverts.
+    fn dispatch_ctor(&mut self, payload: &[u8]) {
+        if let Err(revert) = self.try_dispatch_ctor(payload) {
+            revert.abort()
+        }
+    }
+
+    /// Handle the call of the contract: dispatch its input and end it with the result
+    /// passed to `ret`, like `owasm_ethereum::ret`, or with `Revert::abort`
+    ///
+    /// This is what the `call` export generated by `#[contract]` runs.
+    fn handle_call(&mut self, input: &[u8], ret: fn(&[u8]) -> !) -> ! {
+        match self.try_dispatch(input) {
+            Ok(result) => ret(&result),
+            Err(revert) => revert.abort(),
+        }
+    }
 }
@@ -0,0 +1,338 @@
+//! Packed encoding, as produced by `abi.encodePacked` of Solidity
+
+use super::{common::is_fixed_bytes, types::*, AbiType, Sink};
+use lib::*;
+
+/// Types which have packed (non-standard) encoding
+///
+/// Static types take only the bytes of their own width, `bytes` and `string`
+/// are copied as is and members of arrays are padded to 32 bytes each,
+/// following the rules of Solidity.
+pub trait PackedAbiType {
+    /// Push packed type to data sink
+    /// Should never be called manually! Use sink.push(val)
+    fn encode_packed(self, sink: &mut PackedSink);
+}
+
+/// Static types which can be members of arrays in packed encoding
+///
+/// Members of arrays take their standard encoding, so arrays of dynamic
+/// types, which have no packed encoding, don't implement `PackedAbiType`:
+///
+/// ```compile_fail
+/// # use owasm_abi::eth::encode_packed;
+/// encode_packed(vec!["dynamic".to_owned()]);
+/// ```
+///
+/// Solidity rejects nested arrays and structs there, so neither arrays,
+/// besides `bytesN`, nor tuples are members:
+///
+/// ```compile_fail
+/// # use owasm_abi::eth::encode_packed;
+/// encode_packed(vec![[1u8; 40]]);
+/// ```
+///
+/// ```compile_fail
+/// # use owasm_abi::eth::encode_packed;
+/// encode_packed(vec![(1u32, 2u32)]);
+/// ```
+pub trait PackedMember: AbiType {}
+
+/// Sink for packed encoding, without padding and offsets
+pub struct PackedSink {
+    buffer: Vec<u8>,
+}
+
+impl PackedSink {
+    /// New empty sink
+    pub fn new() -> Self {
+        PackedSink { buffer: Vec::new() }
+    }
+
+    /// Consume `val` to the Sink
+    pub fn push<T: PackedAbiType>(&mut self, val: T) {
+        val.encode_packed(self)
+    }
+
+    /// Consume current Sink to produce a vector with content
+    pub fn finalize(self) -> Vec<u8> {
+        self.buffer
+    }
+
+    /// Mutable reference to the Sink buffer
+    pub fn buffer_mut(&mut self) -> &mut Vec<u8> {
+        &mut self.buffer
+    }
+}
+
+impl Default for PackedSink {
+    fn default() -> Self {
+        PackedSink::new()
+    }
+}
+
+/// Packed encoding of the value, pass a tuple to encode several values
+pub fn encode_packed<T: PackedAbiType>(val: T) -> Vec<u8> {
+    let mut sink = PackedSink::new();
+    sink.push(val);
+    sink.finalize()
+}
+
+macro_rules! packed_int_impl {
+    ($($t: ty),*) => {
+        $(
+            impl PackedAbiType for $t {
+                fn encode_packed(self, sink: &mut PackedSink) {
+                    sink.buffer_mut().extend_from_slice(&self.to_be_bytes());
+                }
+            }
+        )*
+    };
+}
+
+packed_int_impl!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128);
+
+macro_rules! packed_member_impl {
+    ($($t: ty),*) => {
+        $(impl PackedMember for $t {})*
+    };
+}
+
+packed_member_impl!(
+    u8, u16, u32, u64, u128, i8, i16, i32, i64, i128, bool, U256, I256, Address, H256
+);
+
+impl<const BITS: usize> PackedMember for Uint<BITS> {}
+
+impl<const BITS: usize> PackedMember for Int<BITS> {}
+
+macro_rules! packed_fixed_bytes_impl {
+    ($($n: expr),*) => {
+        $(impl PackedMember for [u8; $n] {})*
+    };
+}
+
+packed_fixed_bytes_impl!(
+    1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26,
+    27, 28, 29, 30, 31, 32
+);
+
+impl PackedAbiType for bool {
+    fn encode_packed(self, sink: &mut PackedSink) {
+        sink.buffer_mut().push(self as u8);
+    }
+}
+
+impl PackedAbiType for U256 {
+    fn encode_packed(self, sink: &mut PackedSink) {
+        let mut bytes = [0u8; 32];
+        self.to_big_endian(&mut bytes);
+        sink.buffer_mut().extend_from_slice(&bytes);
+    }
+}
+
+impl PackedAbiType for I256 {
+    fn encode_packed(self, sink: &mut PackedSink) {
+        self.into_raw().encode_packed(sink)
+    }
+}
+
+impl<const BITS: usize> PackedAbiType for Uint<BITS> {
+    fn encode_packed(self, sink: &mut PackedSink) {
+        let mut bytes = [0u8; 32];
+        self.value().to_big_endian(&mut bytes);
+        sink.buffer_mut().extend_from_slice(&bytes[32 - BITS / 8..]);
+    }
+}
+
+impl<const BITS: usize> PackedAbiType for Int<BITS> {
+    fn encode_packed(self, sink: &mut PackedSink) {
+        // two's complement of the value truncated to its width
+        let mut bytes = [0u8; 32];
+        self.value().into_raw().to_big_endian(&mut bytes);
+        sink.buffer_mut().extend_from_slice(&bytes[32 - BITS / 8..]);
+    }
+}
+
+impl PackedAbiType for Address {
+    fn encode_packed(self, sink: &mut PackedSink) {
+        sink.buffer_mut().extend_from_slice(self.as_ref());
+    }
+}
+
+impl PackedAbiType for H256 {
+    fn encode_packed(self, sink: &mut PackedSink) {
+        sink.buffer_mut().extend_from_slice(self.as_ref());
+    }
+}
+
+impl PackedAbiType for String {
+    fn encode_packed(self, sink: &mut PackedSink) {
+        sink.buffer_mut().extend_from_slice(self.as_bytes());
+    }
+}
+
+impl<'a> PackedAbiType for &'a str {
+    fn encode_packed(self, sink: &mut PackedSink) {
+        sink.buffer_mut().extend_from_slice(self.as_bytes());
+    }
+}
+
+impl<'a> PackedAbiType for &'a [u8] {
+    fn encode_packed(self, sink: &mut PackedSink) {
+        sink.buffer_mut().extend_from_slice(self);
+    }
+}
+
+/// Members of `bytes` and `bytesN` are copied as is, members of other arrays
+/// take their standard encoding, padded to 32 bytes each.
+fn encode_packed_members<T: PackedMember>(members: Vec<T>, is_bytes: bool, sink: &mut PackedSink) {
+    let len = members.len();
+    let start = sink.buffer_mut().len();
+    let mut members_sink = Sink::new(0);
+    if T::IS_BYTE && !is_bytes {
+        for member in members {
+            members_sink.push(member);
+        }
+    } else {
+        T::encode_members(members, &mut members_sink);
+    }
+    members_sink.drain_to(sink.buffer_mut());
+    if T::IS_BYTE && is_bytes {
+        sink.buffer_mut().truncate(start + len);
+    }
+}
+
+impl<T: PackedMember> PackedAbiType for Vec<T> {
+    fn encode_packed(self, sink: &mut PackedSink) {
+        encode_packed_members(self, true, sink)
+    }
+}
+
+impl<T: PackedMember, const N: usize> PackedAbiType for [T; N] {
+    fn encode_packed(self, sink: &mut PackedSink) {
+        // longer `[u8; N]` are `uint8[N]`, padded to one word per member
+        encode_packed_members(Vec::from(self), is_fixed_bytes::<T>(N), sink)
+    }
+}
+
+macro_rules! packed_tuple_impls {
+    ($(
+        $Tuple:ident {
+            $(($idx:tt) -> $T:ident)+
+        }
+    )+) => {
+        $(
+            impl<$($T:PackedAbiType),+> PackedAbiType for ($($T,)+) {
+                fn encode_packed(self, sink: &mut PackedSink) {
+                    $(sink.push(self.$idx);)+
+                }
+            }
+        )+
+    }
+}
+
+packed_tuple_impls! {
+    Tuple1 {
+        (0) -> A
+    }
+    Tuple2 {
+        (0) -> A
+        (1) -> B
+    }
+    Tuple3 {
+        (0) -> A
+        (1) -> B
+        (2) -> C
+    }
+    Tuple4 {
+        (0) -> A
+        (1) -> B
+        (2) -> C
+        (3) -> D
+    }
+    Tuple5 {
+        (0) -> A
+        (1) -> B
+        (2) -> C
+        (3) -> D
+        (4) -> E
+    }
+    Tuple6 {
+        (0) -> A
+        (1) -> B
+        (2) -> C
+        (3) -> D
+        (4) -> E
+        (5) -> F
+    }
+    Tuple7 {
+        (0) -> A
+        (1) -> B
+        (2) -> C
+        (3) -> D
+        (4) -> E
+        (5) -> F
+        (6) -> G
+    }
+    Tuple8 {
+        (0) -> A
+        (1) -> B
+        (2) -> C
+        (3) -> D
+        (4) -> E
+        (5) -> F
+        (6) -> G
+        (7) -> H
+    }
+    Tuple9 {
+        (0) -> A
+        (1) -> B
+        (2) -> C
+        (3) -> D
+        (4) -> E
+        (5) -> F
+        (6) -> G
+        (7) -> H
+        (8) -> I
+    }
+    Tuple10 {
+        (0) -> A
+        (1) -> B
+        (2) -> C
+        (3) -> D
+        (4) -> E
+        (5) -> F
+        (6) -> G
+        (7) -> H
+        (8) -> I
+        (9) -> J
+    }
+    Tuple11 {
+        (0) -> A
+        (1) -> B
+        (2) -> C
+        (3) -> D
+        (4) -> E
+        (5) -> F
+        (6) -> G
+        (7) -> H
+        (8) -> I
+        (9) -> J
+        (10) -> K
+    }
+    Tuple12 {
+        (0) -> A
+        (1) -> B
+        (2) -> C
+        (3) -> D
+        (4) -> E
+        (5) -> F
+        (6) -> G
+        (7) -> H
+        (8) -> I
+        (9) -> J
+        (10) -> K
+        (11) -> L
+    }
+}
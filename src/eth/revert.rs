@@ -0,0 +1,186 @@
+//! Revert reasons, as understood by the EVM tooling
+
+use super::{types::*, Error, Sink, Stream};
+use byteorder::{BigEndian, ByteOrder};
+use lib::*;
+
+/// Selector of `Error(string)`, the revert reason given to `require` and `revert` in Solidity
+pub const ERROR_SELECTOR: u32 = 0x08c3_79a0;
+
+/// Selector of `Panic(uint256)`, the revert reason of failed assertions and
+/// arithmetic errors in Solidity
+pub const PANIC_SELECTOR: u32 = 0x4e48_7b71;
+
+/// Decoded revert reason
+#[derive(Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub enum RevertReason {
+    /// `Error(string)` with its message
+    Error(String),
+    /// `Panic(uint256)` with its code, like `0x01` for failed assertions
+    /// or `0x11` for arithmetic overflow
+    Panic(U256),
+}
+
+impl fmt::Display for RevertReason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            RevertReason::Error(ref message) => f.write_str(message),
+            RevertReason::Panic(ref code) => {
+                let mut word = [0u8; 32];
+                code.to_big_endian(&mut word);
+                let start = word.iter().position(|byte| *byte != 0).unwrap_or(31);
+                f.write_str("panic code 0x")?;
+                for byte in &word[start..] {
+                    write!(f, "{:02x}", byte)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Error a contract reverts with
+///
+/// Implemented by the errors declared with `#[error]` in an `eth_abi` interface,
+/// methods returning `Result<T, E>` revert with the payload of `E` when they fail.
+pub trait ContractError: Sized {
+    /// Encode revert payload, starting with the selector of the error
+    fn encode(self) -> Vec<u8>;
+
+    /// Decode the error from its revert payload
+    fn decode(payload: &[u8]) -> Result<Self, Error>;
+}
+
+impl ContractError for RevertReason {
+    fn encode(self) -> Vec<u8> {
+        match self {
+            RevertReason::Error(message) => encode_error(message.as_str()),
+            RevertReason::Panic(code) => encode_panic(code),
+        }
+    }
+
+    fn decode(payload: &[u8]) -> Result<Self, Error> {
+        decode_revert(payload)
+    }
+}
+
+/// Payload a dispatched call reverts with
+///
+/// Returned by `EndpointInterface::try_dispatch`, `abort` fails the call with it.
+#[derive(Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct Revert(Vec<u8>);
+
+impl Revert {
+    /// Revert with the given payload as is
+    pub fn new(payload: Vec<u8>) -> Self {
+        Revert(payload)
+    }
+
+    /// Revert with `Error(string)` reason
+    pub fn error<R: fmt::Display + ?Sized>(reason: &R) -> Self {
+        Revert(encode_error(reason))
+    }
+
+    /// Payload to revert with
+    pub fn payload(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Consume the revert to get its payload
+    pub fn into_payload(self) -> Vec<u8> {
+        self.0
+    }
+
+    /// Decode `Error(string)` or `Panic(uint256)` reason of the payload
+    pub fn reason(&self) -> Result<RevertReason, Error> {
+        decode_revert(&self.0)
+    }
+
+    /// Fail the call with the payload
+    ///
+    /// The host has no call to revert with return data, so on wasm the payload is
+    /// handed to its `panic` exit, which fails the call with the payload attached.
+    /// Elsewhere, e.g. in tests, it panics with the revert as `Display` shows it.
+    pub fn abort(self) -> ! {
+        #[cfg(target_arch = "wasm32")]
+        unsafe {
+            external::panic(self.0.as_ptr(), self.0.len() as u32)
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        panic!("{}", self)
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+mod external {
+    extern "C" {
+        // failure exit of the host, the panic handler of `owasm-std` ends the call with it too
+        pub fn panic(payload_ptr: *const u8, payload_len: u32) -> !;
+    }
+}
+
+impl<E: ContractError> From<E> for Revert {
+    fn from(err: E) -> Self {
+        Revert(err.encode())
+    }
+}
+
+impl fmt::Display for Revert {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.reason() {
+            Ok(reason) => fmt::Display::fmt(&reason, f),
+            Err(_) => {
+                f.write_str("revert payload 0x")?;
+                for byte in &self.0 {
+                    write!(f, "{:02x}", byte)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Encode `Error(string)` revert payload with the given reason
+///
+/// Takes anything printable, so that `format_args!` can build the reason
+/// without allocating a string first.
+pub fn encode_error<R: fmt::Display + ?Sized>(reason: &R) -> Vec<u8> {
+    let mut message = String::new();
+    fmt::Write::write_fmt(&mut message, format_args!("{}", reason))
+        .expect("writing to a string cannot fail");
+    encode_revert(ERROR_SELECTOR, message)
+}
+
+/// Encode `Panic(uint256)` revert payload with the given code
+pub fn encode_panic(code: U256) -> Vec<u8> {
+    encode_revert(PANIC_SELECTOR, code)
+}
+
+fn encode_revert<T: super::AbiType>(selector: u32, val: T) -> Vec<u8> {
+    let mut sink = Sink::new(1);
+    sink.push(val);
+    let mut result = vec![0u8; 4];
+    BigEndian::write_u32(&mut result, selector);
+    sink.drain_to(&mut result);
+    result
+}
+
+/// Decode `Error(string)` or `Panic(uint256)` revert payload
+///
+/// Fails with `Error::UnknownSelector` for any other payload, including the
+/// empty one of a revert without a reason.
+pub fn decode_revert(payload: &[u8]) -> Result<RevertReason, Error> {
+    if payload.len() < 4 {
+        return Err(Error::UnknownSelector);
+    }
+    let mut stream = Stream::new(&payload[4..]);
+    let reason = match BigEndian::read_u32(&payload[..4]) {
+        ERROR_SELECTOR => RevertReason::Error(stream.pop()?),
+        PANIC_SELECTOR => RevertReason::Panic(stream.pop()?),
+        _ => return Err(Error::UnknownSelector),
+    };
+    stream.finish()?;
+    Ok(reason)
+}
@@ -0,0 +1,282 @@
+//! Human-readable function signatures
+
+use super::{util, ParamType};
+use byteorder::{BigEndian, ByteOrder};
+use lib::*;
+
+/// Error for parsing human-readable signatures
+#[derive(Debug, PartialEq, Eq)]
+pub enum SignatureError {
+    /// Function name is missing or is not a valid identifier
+    InvalidName,
+    /// Type is unknown or malformed
+    InvalidType,
+    /// Unexpected token or end of input
+    UnexpectedToken,
+    /// Tuples and arrays are nested more than 64 levels deep
+    TooDeep,
+}
+
+impl fmt::Display for SignatureError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match *self {
+            SignatureError::InvalidName => "invalid function name",
+            SignatureError::InvalidType => "invalid type",
+            SignatureError::UnexpectedToken => "unexpected token",
+            SignatureError::TooDeep => "type is nested too deep",
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+impl ::std::error::Error for SignatureError {}
+
+/// Function signature parsed from its human-readable form
+///
+/// Accepts both the canonical form, like `transfer(address,uint256)`,
+/// and the Solidity declaration form, like
+/// `function balanceOf(address owner) view returns (uint256)`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Signature {
+    /// Function name
+    pub name: String,
+    /// Types of the function arguments
+    pub inputs: Vec<ParamType>,
+    /// Types of the function results, empty if not declared
+    pub outputs: Vec<ParamType>,
+}
+
+/// Nesting limit of tuples and arrays, so that the recursion can't overflow the stack
+const MAX_DEPTH: usize = 64;
+
+/// Keywords allowed after the argument list of a function declaration
+const MODIFIERS: &[&str] = &[
+    "external",
+    "public",
+    "internal",
+    "private",
+    "view",
+    "pure",
+    "payable",
+    "nonpayable",
+    "constant",
+    "virtual",
+    "override",
+];
+
+impl Signature {
+    /// Parse human-readable signature
+    pub fn parse(input: &str) -> Result<Self, SignatureError> {
+        let mut tokens = Tokens { input, depth: 0 };
+
+        let mut name = tokens.next().ok_or(SignatureError::InvalidName)?;
+        if name == "function" {
+            name = tokens.next().ok_or(SignatureError::InvalidName)?;
+        }
+        if !is_identifier(name) {
+            return Err(SignatureError::InvalidName);
+        }
+
+        tokens.expect("(")?;
+        let inputs = parse_params(&mut tokens)?;
+
+        let mut outputs = Vec::new();
+        while let Some(token) = tokens.next() {
+            if token == "returns" {
+                tokens.expect("(")?;
+                outputs = parse_params(&mut tokens)?;
+            } else if !MODIFIERS.contains(&token) {
+                return Err(SignatureError::UnexpectedToken);
+            }
+        }
+
+        Ok(Signature {
+            name: name.to_owned(),
+            inputs,
+            outputs,
+        })
+    }
+
+    /// Canonical representation of the signature, as used for the selector
+    pub fn canonical(&self) -> String {
+        let mut result = self.name.clone();
+        result.push('(');
+        for (i, param) in self.inputs.iter().enumerate() {
+            if i != 0 {
+                result.push(',');
+            }
+            result.push_str(&param.to_string());
+        }
+        result.push(')');
+        result
+    }
+
+    /// First 4 bytes of the Keccak hash of the canonical signature
+    pub fn selector(&self) -> u32 {
+        BigEndian::read_u32(&util::keccak(self.canonical().as_bytes()).as_ref()[0..4])
+    }
+}
+
+impl str::FromStr for Signature {
+    type Err = SignatureError;
+
+    fn from_str(input: &str) -> Result<Self, SignatureError> {
+        Signature::parse(input)
+    }
+}
+
+impl str::FromStr for ParamType {
+    type Err = SignatureError;
+
+    /// Parse single type, like `uint256[]` or `(address,bytes32)[2]`
+    fn from_str(input: &str) -> Result<Self, SignatureError> {
+        let mut tokens = Tokens { input, depth: 0 };
+        let param = parse_type(&mut tokens)?;
+        match tokens.next() {
+            Some(_) => Err(SignatureError::UnexpectedToken),
+            None => Ok(param),
+        }
+    }
+}
+
+/// Splits input into words and the punctuation of type lists
+struct Tokens<'a> {
+    input: &'a str,
+    depth: usize,
+}
+
+impl<'a> Tokens<'a> {
+    fn peek(&self) -> Option<&'a str> {
+        let input = self.input.trim_start();
+        let first = input.chars().next()?;
+        let len = if "()[],".contains(first) || !is_word_char(first) {
+            first.len_utf8()
+        } else {
+            input.find(|c| !is_word_char(c)).unwrap_or(input.len())
+        };
+        Some(&input[..len])
+    }
+
+    fn next(&mut self) -> Option<&'a str> {
+        let token = self.peek()?;
+        let input = self.input.trim_start();
+        self.input = &input[token.len()..];
+        Some(token)
+    }
+
+    fn expect(&mut self, expected: &str) -> Result<(), SignatureError> {
+        match self.next() {
+            Some(token) if token == expected => Ok(()),
+            _ => Err(SignatureError::UnexpectedToken),
+        }
+    }
+
+    fn nest(&mut self) -> Result<(), SignatureError> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            return Err(SignatureError::TooDeep);
+        }
+        Ok(())
+    }
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_' || c == '$'
+}
+
+fn is_identifier(token: &str) -> bool {
+    token.chars().next().map_or(false, |c| !c.is_ascii_digit() && is_word_char(c))
+}
+
+/// Parse comma-separated parameters up to the closing parenthesis
+///
+/// Each type may be followed by words which do not affect the signature,
+/// like data location, `indexed` and the parameter name.
+fn parse_params(tokens: &mut Tokens) -> Result<Vec<ParamType>, SignatureError> {
+    let mut params = Vec::new();
+    if tokens.peek() == Some(")") {
+        tokens.next();
+        return Ok(params);
+    }
+    loop {
+        params.push(parse_type(tokens)?);
+        loop {
+            match tokens.next() {
+                Some(",") => break,
+                Some(")") => return Ok(params),
+                Some(token) if is_identifier(token) => continue,
+                _ => return Err(SignatureError::UnexpectedToken),
+            }
+        }
+    }
+}
+
+fn parse_type(tokens: &mut Tokens) -> Result<ParamType, SignatureError> {
+    let depth = tokens.depth;
+    let mut param = match tokens.next() {
+        Some("(") => parse_tuple(tokens)?,
+        Some("tuple") => {
+            tokens.expect("(")?;
+            parse_tuple(tokens)?
+        }
+        Some(name) => parse_elementary(name)?,
+        None => return Err(SignatureError::UnexpectedToken),
+    };
+
+    tokens.depth = depth;
+    while tokens.peek() == Some("[") {
+        tokens.next();
+        tokens.nest()?;
+        param = match tokens.next() {
+            Some("]") => ParamType::Array(Box::new(param)),
+            Some(len) => {
+                let len = len.parse().map_err(|_| SignatureError::InvalidType)?;
+                tokens.expect("]")?;
+                ParamType::FixedArray(Box::new(param), len)
+            }
+            None => return Err(SignatureError::UnexpectedToken),
+        };
+    }
+    tokens.depth = depth;
+    Ok(param)
+}
+
+fn parse_tuple(tokens: &mut Tokens) -> Result<ParamType, SignatureError> {
+    tokens.nest()?;
+    Ok(ParamType::Tuple(parse_params(tokens)?))
+}
+
+fn parse_elementary(name: &str) -> Result<ParamType, SignatureError> {
+    let param = match name {
+        "address" => ParamType::Address,
+        "bool" => ParamType::Bool,
+        "string" => ParamType::String,
+        "bytes" => ParamType::Bytes,
+        "byte" => ParamType::FixedBytes(1),
+        "uint" => ParamType::Uint(256),
+        "int" => ParamType::Int(256),
+        _ => {
+            if let Some(bits) = name.strip_prefix("uint") {
+                ParamType::Uint(parse_width(bits, 8, 256)?)
+            } else if let Some(bits) = name.strip_prefix("int") {
+                ParamType::Int(parse_width(bits, 8, 256)?)
+            } else if let Some(len) = name.strip_prefix("bytes") {
+                ParamType::FixedBytes(parse_width(len, 1, 32)?)
+            } else {
+                return Err(SignatureError::InvalidType);
+            }
+        }
+    };
+    Ok(param)
+}
+
+/// Parse bit width of integers (multiple of 8) or length of fixed bytes
+fn parse_width(digits: &str, step: usize, max: usize) -> Result<usize, SignatureError> {
+    if digits.starts_with('0') {
+        return Err(SignatureError::InvalidType);
+    }
+    match digits.parse::<usize>() {
+        Ok(width) if width <= max && width % step == 0 => Ok(width),
+        _ => Err(SignatureError::InvalidType),
+    }
+}
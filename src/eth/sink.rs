@@ -3,6 +3,29 @@
 use super::AbiType;
 use lib::*;
 
+/// Error for finalizing a Sink which got a different number of words
+/// than its declared capacity
+#[derive(Debug, PartialEq, Eq)]
+pub struct CapacityMismatch {
+    /// Number of words declared in `Sink::new`
+    pub expected: usize,
+    /// Number of words actually pushed
+    pub actual: usize,
+}
+
+impl fmt::Display for CapacityMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "expected {} words to be pushed to the sink, got {}",
+            self.expected, self.actual
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl ::std::error::Error for CapacityMismatch {}
+
 /// Sink for returning number of arguments
 pub struct Sink {
     capacity: usize,
@@ -20,8 +43,9 @@ impl Sink {
         }
     }
 
+    /// Offset of the next dynamic value, relative to the start of the sink
     fn top_ptr(&self) -> usize {
-        self.preamble.capacity() + self.heap.len()
+        self.capacity + self.heap.len()
     }
 
     /// Consume `val` to the Sink
@@ -29,14 +53,19 @@ impl Sink {
         if T::IS_FIXED {
             val.encode(self)
         } else {
-            let mut nested_sink = Sink::new(1);
-            val.encode(&mut nested_sink);
-            let top_ptr = self.top_ptr() as u32;
-            nested_sink.drain_to(&mut self.heap);
-            self.push(top_ptr);
+            self.push_nested(|sink| val.encode(sink))
         }
     }
 
+    /// Encode dynamic value to the heap, referencing it by an offset in the preamble
+    pub(crate) fn push_nested<F: FnOnce(&mut Sink)>(&mut self, encode: F) {
+        let mut nested_sink = Sink::new(1);
+        encode(&mut nested_sink);
+        let top_ptr = self.top_ptr() as u32;
+        nested_sink.drain_to(&mut self.heap);
+        self.push(top_ptr);
+    }
+
     /// Drain current Sink to the target vector
     pub fn drain_to(self, target: &mut Vec<u8>) {
         let preamble = self.preamble;
@@ -47,20 +76,28 @@ impl Sink {
     }
 
     /// Consume current Sink to produce a vector with content.
-    /// May panic if declared number of arguments does not match the resulting number of bytes should be produced.
-    pub fn finalize_panicking(self) -> Vec<u8> {
+    /// Fails if declared number of arguments does not match the number of words pushed.
+    pub fn finalize(self) -> Result<Vec<u8>, CapacityMismatch> {
         if self.preamble.len() != self.capacity {
-            panic!(
-                "Underflow of pushed parameters {}/{}!",
-                self.preamble.len(),
-                self.capacity
-            );
+            return Err(CapacityMismatch {
+                expected: self.capacity / 32,
+                actual: (self.preamble.len() + 31) / 32,
+            });
         }
         let mut result = self.preamble;
         let heap = self.heap;
 
         result.extend_from_slice(&heap);
-        result
+        Ok(result)
+    }
+
+    /// Consume current Sink to produce a vector with content.
+    /// May panic if declared number of arguments does not match the resulting number of bytes should be produced.
+    pub fn finalize_panicking(self) -> Vec<u8> {
+        match self.finalize() {
+            Ok(result) => result,
+            Err(err) => panic!("Underflow of pushed parameters {}/{}!", err.actual, err.expected),
+        }
     }
 
     /// Mutable reference to the Sink preamble
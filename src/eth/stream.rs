@@ -1,12 +1,21 @@
 //! Stream module
 
-use super::{AbiType, Error};
+use super::{AbiType, BorrowedAbiType, DecodeError, Error};
 use lib::*;
 
 /// Stream interpretation of incoming payload
 pub struct Stream<'a> {
     payload: &'a [u8],
     position: usize,
+    strict: bool,
+    // offset of the first dynamic member, strict mode only
+    tail_start: Option<usize>,
+    // end of the last dynamic member
+    tail_end: usize,
+    // offset of the payload within the payload of the outermost stream
+    base: usize,
+    // offset of the innermost value which failed to decode
+    error_offset: Option<usize>,
 }
 
 impl<'a> Stream<'a> {
@@ -15,18 +24,154 @@ impl<'a> Stream<'a> {
         Stream {
             payload: raw,
             position: 0,
+            strict: false,
+            tail_start: None,
+            tail_end: 0,
+            base: 0,
+            error_offset: None,
         }
     }
 
+    /// New stream for known payload which only accepts the canonical encoding
+    ///
+    /// Non-zero padding, offsets other than the ones of a contiguous tail and
+    /// trailing bytes (see `finish`) are rejected, so that every value has
+    /// exactly one accepted encoding.
+    pub fn strict(raw: &'a [u8]) -> Self {
+        Stream {
+            strict: true,
+            ..Stream::new(raw)
+        }
+    }
+
+    /// Whether the stream only accepts the canonical encoding
+    pub fn is_strict(&self) -> bool {
+        self.strict
+    }
+
     /// Pop next argument of known type
     pub fn pop<T: AbiType>(&mut self) -> Result<T, Error> {
-        if T::IS_FIXED {
+        let start = self.position;
+        let result = if T::IS_FIXED {
             T::decode(self)
         } else {
-            let offset = u32::decode(self)?;
-            let mut nested_stream = Stream::new(&self.payload[offset as usize..]);
-            T::decode(&mut nested_stream)
+            self.pop_nested(T::decode)
+        };
+        self.track_error(start, result)
+    }
+
+    /// Pop next argument of known type, borrowing it from the payload
+    pub fn pop_borrowed<T: BorrowedAbiType<'a>>(&mut self) -> Result<T, Error> {
+        let start = self.position;
+        let result = self.pop_nested(T::decode);
+        self.track_error(start, result)
+    }
+
+    /// Remember where the value starting at `start` failed to decode,
+    /// unless one of its members already did
+    pub(crate) fn track_error<T>(
+        &mut self,
+        start: usize,
+        result: Result<T, Error>,
+    ) -> Result<T, Error> {
+        if result.is_err() && self.error_offset.is_none() {
+            self.error_offset = Some(self.base + start);
         }
+        result
+    }
+
+    /// Attach the offset of the value which failed to decode to the error
+    pub fn error(&mut self, kind: Error) -> DecodeError {
+        DecodeError {
+            kind,
+            offset: self.error_offset.take().unwrap_or(self.base + self.position),
+            argument: None,
+            expected: None,
+        }
+    }
+
+    /// Decode dynamic value from the payload, referenced by an offset at the current position
+    pub(crate) fn pop_nested<T, F>(&mut self, decode: F) -> Result<T, Error>
+    where
+        F: FnOnce(&mut Stream<'a>) -> Result<T, Error>,
+    {
+        let offset = u32::decode(self)? as usize;
+        if offset > self.payload.len() {
+            return Err(Error::InvalidOffset);
+        }
+        if self.strict {
+            match self.tail_start {
+                // checked against the end of the head in `end`
+                None => self.tail_start = Some(offset),
+                Some(_) if offset != self.tail_end => return Err(Error::NonCanonicalOffset),
+                Some(_) => {}
+            }
+        }
+
+        let mut nested_stream = Stream {
+            strict: self.strict,
+            base: self.base + offset,
+            ..Stream::new(&self.payload[offset..])
+        };
+        let result = decode(&mut nested_stream);
+        let result = nested_stream.track_error(nested_stream.position, result);
+        self.error_offset = nested_stream.error_offset;
+        let result = result?;
+        self.tail_end = offset + nested_stream.end()?;
+        Ok(result)
+    }
+
+    /// Decode consecutive members of an array starting at the current position,
+    /// with offsets of dynamic members relative to the first member
+    pub(crate) fn pop_members<T, F>(&mut self, decode: F) -> Result<T, Error>
+    where
+        F: FnOnce(&mut Stream<'a>) -> Result<T, Error>,
+    {
+        let start = self.position;
+        let mut nested_stream = Stream {
+            strict: self.strict,
+            base: self.base + start,
+            ..Stream::new(&self.payload[start..])
+        };
+        let result = decode(&mut nested_stream);
+        self.error_offset = nested_stream.error_offset;
+        let result = result?;
+        self.position = start + nested_stream.end()?;
+        Ok(result)
+    }
+
+    /// End of the data decoded so far, including the tail of dynamic members
+    fn end(&self) -> Result<usize, Error> {
+        match self.tail_start {
+            None => Ok(self.position),
+            Some(tail_start) if tail_start == self.position => Ok(self.tail_end),
+            Some(_) => Err(Error::NonCanonicalOffset),
+        }
+    }
+
+    /// Finish decoding, in strict mode the whole payload should have been consumed
+    pub fn finish(&self) -> Result<(), Error> {
+        let end = self.end()?;
+        if self.strict && end != self.payload.len() {
+            return Err(Error::TrailingBytes);
+        }
+        Ok(())
+    }
+
+    /// Borrow next `len` bytes of the payload, advancing stream to the next 32 byte step
+    pub fn take(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        let previous_position = self.advance(len)?;
+        self.finish_advance();
+        if self.strict {
+            let padding = self
+                .payload
+                .get(previous_position + len..self.position)
+                .ok_or(Error::UnexpectedEof)?;
+            if padding.iter().any(|b| *b != 0) {
+                return Err(Error::InvalidPadding);
+            }
+        }
+        Ok(&self.payload[previous_position..previous_position + len])
     }
 
     /// Current position for the stream
@@ -36,7 +181,11 @@ impl<'a> Stream<'a> {
 
     /// Advance stream position for `amount` bytes
     pub fn advance(&mut self, amount: usize) -> Result<usize, Error> {
-        if self.position + amount > self.payload.len() {
+        let new_position = self
+            .position
+            .checked_add(amount)
+            .ok_or(Error::LengthOverflow)?;
+        if new_position > self.payload.len() {
             return Err(Error::UnexpectedEof);
         }
 
@@ -57,8 +206,26 @@ impl<'a> Stream<'a> {
         self.payload
     }
 
+    /// Check that `len` array members of `head_words` words each can fit into the rest of the stream
+    ///
+    /// Bounds attacker-chosen lengths before anything gets allocated for them.
+    pub(crate) fn check_members(&self, len: usize, head_words: usize) -> Result<(), Error> {
+        if len > self.remaining() / 32 / cmp::max(head_words, 1) {
+            return Err(Error::LengthOverflow);
+        }
+        Ok(())
+    }
+
+    /// Number of bytes left in the stream
+    pub fn remaining(&self) -> usize {
+        self.payload.len().saturating_sub(self.position)
+    }
+
     /// Peek next byte in stream
-    pub fn peek(&self) -> u8 {
-        self.payload[self.position]
+    pub fn peek(&self) -> Result<u8, Error> {
+        self.payload
+            .get(self.position)
+            .cloned()
+            .ok_or(Error::UnexpectedEof)
     }
 }
@@ -3,68 +3,956 @@ use lib::*;
 
 #[cfg(feature = "std")]
 mod hextest {
-    use super::super::*;
+    use super::super::{types::*, *};
     use lib::*;
 
     #[test]
-    fn bytes() {
-        let encoded = hex!(
-            "
-			0000000000000000000000000000000000000000000000000000000000000020
-			0000000000000000000000000000000000000000000000000000000000000002
-			1234000000000000000000000000000000000000000000000000000000000000
-		"
+    fn bytes() {
+        let encoded = hex!(
+            "
+			0000000000000000000000000000000000000000000000000000000000000020
+			0000000000000000000000000000000000000000000000000000000000000002
+			1234000000000000000000000000000000000000000000000000000000000000
+		"
+        );
+        let mut stream = Stream::new(&encoded);
+
+        let bytes: Vec<u8> = stream.pop().unwrap();
+
+        assert_eq!(vec![0x12u8, 0x34], bytes);
+    }
+
+    #[test]
+    fn two_bytes() {
+        let encoded = hex!(
+            "
+			0000000000000000000000000000000000000000000000000000000000000040
+			0000000000000000000000000000000000000000000000000000000000000080
+			000000000000000000000000000000000000000000000000000000000000001f
+			1000000000000000000000000000000000000000000000000000000000000200
+			0000000000000000000000000000000000000000000000000000000000000020
+			0010000000000000000000000000000000000000000000000000000000000002
+		"
+        );
+
+        let mut stream = Stream::new(&encoded);
+
+        let bytes1: Vec<u8> = stream.pop().unwrap();
+        let bytes2: Vec<u8> = stream.pop().unwrap();
+
+        assert_eq!(
+            bytes1,
+            hex!("10000000000000000000000000000000000000000000000000000000000002")
+        );
+        assert_eq!(
+            bytes2,
+            hex!("0010000000000000000000000000000000000000000000000000000000000002")
+        );
+    }
+
+    #[test]
+    fn bytes_encode() {
+        assert_eq!(
+            super::single_encode(vec![0x12u8, 0x34]),
+            hex!(
+                "
+				0000000000000000000000000000000000000000000000000000000000000020
+				0000000000000000000000000000000000000000000000000000000000000002
+				1234000000000000000000000000000000000000000000000000000000000000
+			"
+            )
+            .to_vec()
+        );
+    }
+
+    #[test]
+    fn dynamic_tuple() {
+        let encoded = hex!(
+            "
+			0000000000000000000000000000000000000000000000000000000000000020
+			0000000000000000000000000000000000000000000000000000000000000006
+			0000000000000000000000000000000000000000000000000000000000000040
+			0000000000000000000000000000000000000000000000000000000000000003
+			0102030000000000000000000000000000000000000000000000000000000000
+		"
+        );
+
+        let mut stream = Stream::new(&encoded);
+        let tuple: (u64, Vec<u8>) = stream.pop().unwrap();
+        assert_eq!(tuple, (6, vec![1, 2, 3]));
+
+        assert_eq!(super::single_encode(tuple), encoded.to_vec());
+    }
+
+    #[test]
+    fn fixed_array_of_dynamic() {
+        let encoded = hex!(
+            "
+			0000000000000000000000000000000000000000000000000000000000000020
+			0000000000000000000000000000000000000000000000000000000000000040
+			0000000000000000000000000000000000000000000000000000000000000080
+			0000000000000000000000000000000000000000000000000000000000000002
+			0102000000000000000000000000000000000000000000000000000000000000
+			0000000000000000000000000000000000000000000000000000000000000001
+			0300000000000000000000000000000000000000000000000000000000000000
+		"
+        );
+
+        let mut stream = Stream::new(&encoded);
+        let array: [Vec<u8>; 2] = stream.pop().unwrap();
+        assert_eq!(array, [vec![1, 2], vec![3]]);
+
+        assert_eq!(super::single_encode(array), encoded.to_vec());
+    }
+
+    #[test]
+    fn string() {
+        let encoded = hex!(
+            "
+			0000000000000000000000000000000000000000000000000000000000000020
+			0000000000000000000000000000000000000000000000000000000000000004
+			6461766500000000000000000000000000000000000000000000000000000000
+		"
+        );
+
+        let mut stream = Stream::new(&encoded);
+        let string: String = stream.pop().unwrap();
+        assert_eq!(string, "dave");
+
+        assert_eq!(super::single_encode(string), encoded.to_vec());
+    }
+
+    #[test]
+    fn string_invalid_utf8() {
+        let encoded = hex!(
+            "
+			0000000000000000000000000000000000000000000000000000000000000020
+			0000000000000000000000000000000000000000000000000000000000000002
+			c328000000000000000000000000000000000000000000000000000000000000
+		"
+        );
+
+        let mut stream = Stream::new(&encoded);
+        assert_eq!(stream.pop::<String>().unwrap_err(), Error::InvalidUtf8);
+
+        let mut stream = Stream::new(&encoded);
+        assert_eq!(stream.pop_borrowed::<&str>().unwrap_err(), Error::InvalidUtf8);
+    }
+
+    #[test]
+    fn borrowed() {
+        let encoded = hex!(
+            "
+			0000000000000000000000000000000000000000000000000000000000000040
+			0000000000000000000000000000000000000000000000000000000000000080
+			0000000000000000000000000000000000000000000000000000000000000003
+			0102030000000000000000000000000000000000000000000000000000000000
+			0000000000000000000000000000000000000000000000000000000000000004
+			6461766500000000000000000000000000000000000000000000000000000000
+		"
+        );
+
+        let mut stream = Stream::new(&encoded);
+        let bytes: &[u8] = stream.pop_borrowed().unwrap();
+        let string: &str = stream.pop_borrowed().unwrap();
+
+        assert_eq!(bytes, &[1, 2, 3]);
+        assert_eq!(string, "dave");
+        // both point straight into the payload
+        assert_eq!(bytes.as_ptr(), encoded[96..].as_ptr());
+        assert_eq!(string.as_ptr(), encoded[160..].as_ptr());
+    }
+
+    #[test]
+    fn borrowed_eof() {
+        let encoded = hex!(
+            "
+			0000000000000000000000000000000000000000000000000000000000000020
+			0000000000000000000000000000000000000000000000000000000000000021
+			6461766500000000000000000000000000000000000000000000000000000000
+		"
+        );
+
+        let mut stream = Stream::new(&encoded);
+        assert_eq!(stream.pop_borrowed::<&[u8]>().unwrap_err(), Error::UnexpectedEof);
+    }
+
+    #[test]
+    fn string_as_log() {
+        assert_eq!(
+            AsLog::as_log(&String::from("dave")),
+            H256::from(hex!(
+                "5e2393c41c2785095aa424cf3e033319468b6dcebda65e61606ee2ae2a198a87"
+            ))
+        );
+    }
+
+    #[test]
+    fn bytes_as_log() {
+        let dave = H256::from(hex!(
+            "5e2393c41c2785095aa424cf3e033319468b6dcebda65e61606ee2ae2a198a87"
+        ));
+        assert_eq!(AsLog::as_log(&b"dave".to_vec()), dave);
+        assert_eq!(AsLog::as_log(&b"dave"[..]), dave);
+        assert_eq!(
+            AsLog::as_log(&[0xdeu8, 0xad, 0xbe, 0xef]),
+            H256::from(hex!(
+                "deadbeef00000000000000000000000000000000000000000000000000000000"
+            ))
+        );
+    }
+
+    #[test]
+    fn arrays_as_log() {
+        // keccak256(abi.encodePacked(uint256[2]([1, 2])))
+        let expected = H256::from(hex!(
+            "e90b7bceb6e7df5418fb78d8ee546e97c83a08bbccc01a0644d599ccd2a7c2e0"
+        ));
+        assert_eq!(AsLog::as_log(&vec![U256::from(1), U256::from(2)]), expected);
+        assert_eq!(AsLog::as_log(&[1u32, 2]), expected);
+        assert_eq!(
+            AsLog::as_log(&true),
+            H256::from(hex!(
+                "0000000000000000000000000000000000000000000000000000000000000001"
+            ))
+        );
+    }
+
+    #[test]
+    fn dynamic_arrays_as_log() {
+        // members are encoded in place, padded to 32 bytes without their lengths
+        let mut in_place = [0u8; 64];
+        in_place[..2].copy_from_slice(b"ab");
+        in_place[32] = b'c';
+        let expected = util::keccak(&in_place);
+        assert_eq!(AsLog::as_log(&vec!["ab".to_owned(), "c".to_owned()]), expected);
+        assert_eq!(AsLog::as_log(&vec![b"ab".to_vec(), b"c".to_vec()]), expected);
+        assert_eq!(AsLog::as_log(&vec![vec![b"ab".to_vec()], vec![b"c".to_vec()]]), expected);
+        assert_eq!(AsLog::as_log(&vec![String::new()]), util::keccak(&[]));
+        assert_eq!(AsLog::as_log(&Vec::<Uint<7>>::new()), util::keccak(&[]));
+
+        // tuples are encoded member by member, static members take their word
+        let mut in_place = [0u8; 64];
+        in_place[..2].copy_from_slice(b"ab");
+        in_place[63] = 7;
+        let expected = util::keccak(&in_place);
+        assert_eq!(AsLog::as_log(&vec![("ab".to_owned(), 7u8)]), expected);
+        assert_eq!(AsLog::as_log(&[("ab", 7u8)]), expected);
+    }
+
+    #[test]
+    fn strict_canonical() {
+        let encoded = hex!(
+            "
+			0000000000000000000000000000000000000000000000000000000000000060
+			0000000000000000000000001111111111111111111111111111111111111111
+			00000000000000000000000000000000000000000000000000000000000000a0
+			0000000000000000000000000000000000000000000000000000000000000003
+			0102030000000000000000000000000000000000000000000000000000000000
+			0000000000000000000000000000000000000000000000000000000000000004
+			6461766500000000000000000000000000000000000000000000000000000000
+		"
+        );
+
+        let mut stream = Stream::strict(&encoded);
+        let bytes: Vec<u8> = stream.pop().unwrap();
+        let address: Address = stream.pop().unwrap();
+        let string: String = stream.pop().unwrap();
+        stream.finish().unwrap();
+
+        assert_eq!(bytes, vec![1, 2, 3]);
+        assert_eq!(address, Address::from([0x11u8; 20]));
+        assert_eq!(string, "dave");
+    }
+
+    #[test]
+    fn strict_padding() {
+        let encoded = hex!("0102030405000000000000000000000000000000000000000000000000000000");
+        assert_eq!(Stream::new(&encoded).pop::<[u8; 4]>().unwrap(), [1, 2, 3, 4]);
+        assert_eq!(Stream::strict(&encoded).pop::<[u8; 4]>().unwrap_err(), Error::InvalidPadding);
+
+        let encoded = hex!(
+            "
+			0000000000000000000000000000000000000000000000000000000000000020
+			0000000000000000000000000000000000000000000000000000000000000003
+			0102030400000000000000000000000000000000000000000000000000000000
+		"
+        );
+        assert_eq!(Stream::new(&encoded).pop::<Vec<u8>>().unwrap(), vec![1, 2, 3]);
+        assert_eq!(Stream::strict(&encoded).pop::<Vec<u8>>().unwrap_err(), Error::InvalidPadding);
+        assert_eq!(Stream::strict(&encoded).pop_borrowed::<&[u8]>().unwrap_err(), Error::InvalidPadding);
+
+        let encoded = hex!("0000000000000000000000011111111111111111111111111111111111111111");
+        assert_eq!(Stream::new(&encoded).pop::<Address>().unwrap(), Address::from([0x11u8; 20]));
+        assert_eq!(Stream::strict(&encoded).pop::<Address>().unwrap_err(), Error::InvalidPadding);
+    }
+
+    #[test]
+    fn strict_offsets() {
+        // tail of the second string comes first
+        let encoded = hex!(
+            "
+			0000000000000000000000000000000000000000000000000000000000000080
+			0000000000000000000000000000000000000000000000000000000000000040
+			0000000000000000000000000000000000000000000000000000000000000004
+			6461766500000000000000000000000000000000000000000000000000000000
+			0000000000000000000000000000000000000000000000000000000000000004
+			6461766500000000000000000000000000000000000000000000000000000000
+		"
+        );
+        let mut stream = Stream::new(&encoded);
+        assert_eq!(stream.pop::<String>().unwrap(), "dave");
+        assert_eq!(stream.pop::<String>().unwrap(), "dave");
+        stream.finish().unwrap();
+
+        let mut stream = Stream::strict(&encoded);
+        assert_eq!(stream.pop::<String>().unwrap(), "dave");
+        assert_eq!(stream.pop::<String>().unwrap_err(), Error::NonCanonicalOffset);
+
+        // both strings share the same tail
+        let encoded = hex!(
+            "
+			0000000000000000000000000000000000000000000000000000000000000040
+			0000000000000000000000000000000000000000000000000000000000000040
+			0000000000000000000000000000000000000000000000000000000000000004
+			6461766500000000000000000000000000000000000000000000000000000000
+		"
+        );
+        let mut stream = Stream::strict(&encoded);
+        assert_eq!(stream.pop::<String>().unwrap(), "dave");
+        assert_eq!(stream.pop::<String>().unwrap_err(), Error::NonCanonicalOffset);
+
+        // gap between the head and the tail
+        let encoded = hex!(
+            "
+			0000000000000000000000000000000000000000000000000000000000000040
+			0000000000000000000000000000000000000000000000000000000000000000
+			0000000000000000000000000000000000000000000000000000000000000004
+			6461766500000000000000000000000000000000000000000000000000000000
+		"
+        );
+        let mut stream = Stream::strict(&encoded);
+        assert_eq!(stream.pop::<String>().unwrap(), "dave");
+        assert_eq!(stream.finish().unwrap_err(), Error::NonCanonicalOffset);
+    }
+
+    #[test]
+    fn strict_nested_offsets() {
+        // (uint64, string) with a gap inside the tuple
+        let encoded = hex!(
+            "
+			0000000000000000000000000000000000000000000000000000000000000020
+			0000000000000000000000000000000000000000000000000000000000000007
+			0000000000000000000000000000000000000000000000000000000000000060
+			0000000000000000000000000000000000000000000000000000000000000000
+			0000000000000000000000000000000000000000000000000000000000000004
+			6461766500000000000000000000000000000000000000000000000000000000
+		"
+        );
+        let mut stream = Stream::new(&encoded);
+        assert_eq!(stream.pop::<(u64, String)>().unwrap(), (7, "dave".to_owned()));
+
+        let mut stream = Stream::strict(&encoded);
+        assert_eq!(stream.pop::<(u64, String)>().unwrap_err(), Error::NonCanonicalOffset);
+    }
+
+    #[test]
+    fn malformed_offset() {
+        let encoded = hex!("0000000000000000000000000000000000000000000000000000000000001000");
+        let mut stream = Stream::new(&encoded);
+        assert_eq!(stream.pop::<String>().unwrap_err(), Error::InvalidOffset);
+
+        let mut stream = Stream::new(&encoded);
+        assert_eq!(stream.pop_borrowed::<&[u8]>().unwrap_err(), Error::InvalidOffset);
+    }
+
+    #[test]
+    fn malformed_length() {
+        // attacker-chosen array length is rejected before allocating
+        let encoded = hex!(
+            "
+			0000000000000000000000000000000000000000000000000000000000000020
+			00000000000000000000000000000000000000000000000000000000ffffffff
+			0000000000000000000000000000000000000000000000000000000000000001
+		"
+        );
+        let mut stream = Stream::new(&encoded);
+        assert_eq!(stream.pop::<Vec<U256>>().unwrap_err(), Error::LengthOverflow);
+
+        let mut stream = Stream::new(&encoded);
+        assert_eq!(stream.pop::<Vec<u8>>().unwrap_err(), Error::UnexpectedEof);
+
+        // bytes cut short in the middle of the last word
+        let encoded = hex!(
+            "
+			0000000000000000000000000000000000000000000000000000000000000020
+			0000000000000000000000000000000000000000000000000000000000000028
+			6461766564617665646176656461766564617665646176656461766564617665
+		"
+        );
+        let mut stream = Stream::new(&encoded);
+        assert_eq!(stream.pop::<Vec<u8>>().unwrap_err(), Error::UnexpectedEof);
+    }
+
+    #[test]
+    fn malformed_empty() {
+        assert_eq!(Stream::new(&[]).peek().unwrap_err(), Error::UnexpectedEof);
+        assert_eq!(Stream::new(&[]).pop::<i32>().unwrap_err(), Error::UnexpectedEof);
+        assert_eq!(Stream::new(&[]).pop::<i64>().unwrap_err(), Error::UnexpectedEof);
+        assert_eq!(Stream::new(&[]).pop::<String>().unwrap_err(), Error::UnexpectedEof);
+        assert_eq!(Stream::new(&[0xff]).pop::<i32>().unwrap_err(), Error::UnexpectedEof);
+    }
+
+    #[test]
+    fn annotated_payload() {
+        // selector, "dave" with garbage in its padding, true and an extra word
+        let encoded = hex!(
+            "
+			12345678
+			0000000000000000000000000000000000000000000000000000000000000040
+			0000000000000000000000000000000000000000000000000000000000000001
+			0000000000000000000000000000000000000000000000000000000000000004
+			6461766500000000000000000000000000000000000000000000000000000001
+			00000000000000000000000000000000000000000000000000000000000000ff
+		"
+        );
+        let params = [ParamType::String, ParamType::Bool];
+        assert_eq!(
+            annotate(&encoded, &params),
+            concat!(
+                "selector  12345678\n",
+                "0x0000    0000000000000000000000000000000000000000000000000000000000000040  ",
+                "offset  arg0 (string) -> 0x0040\n",
+                "0x0020    0000000000000000000000000000000000000000000000000000000000000001  ",
+                "head    arg1 (bool) = true\n",
+                "0x0040    0000000000000000000000000000000000000000000000000000000000000004  ",
+                "length  arg0 (string) = 4\n",
+                "0x0060    6461766500000000000000000000000000000000000000000000000000000001  ",
+                "data    arg0 (string): 4 bytes + padding, non-zero padding\n",
+                "0x0080    00000000000000000000000000000000000000000000000000000000000000ff  ",
+                "??      not accounted for by any argument\n",
+            )
+        );
+
+        let params = [ParamType::Array(Box::new(ParamType::Uint(256))), ParamType::Bytes];
+        let encoded = hex!(
+            "
+			0000000000000000000000000000000000000000000000000000000000000040
+			0000000000000000000000000000000000000000000000000000000000001000
+			0000000000000000000000000000000000000000000000000000000000000003
+			0000000000000000000000000000000000000000000000000000000000000007
+		"
+        );
+        assert_eq!(
+            annotate(&encoded, &params),
+            concat!(
+                "0x0000    0000000000000000000000000000000000000000000000000000000000000040  ",
+                "offset  arg0 (uint256[]) -> 0x0040\n",
+                "0x0020    0000000000000000000000000000000000000000000000000000000000001000  ",
+                "offset  arg1 (bytes) points outside of the payload\n",
+                "0x0040    0000000000000000000000000000000000000000000000000000000000000003  ",
+                "length  arg0 (uint256[]) = 3\n",
+                "0x0060    0000000000000000000000000000000000000000000000000000000000000007  ",
+                "head    arg0[0] (uint256) = 7\n",
+                "note: 0x0080 is past the end of the payload: head    arg0[1] (uint256)\n",
+                "note: 0x00a0 is past the end of the payload: head    arg0[2] (uint256)\n",
+            )
+        );
+    }
+
+    #[test]
+    fn error_context() {
+        // (7, "dave"), [true, 2]
+        let encoded = hex!(
+            "
+			0000000000000000000000000000000000000000000000000000000000000060
+			0000000000000000000000000000000000000000000000000000000000000001
+			0000000000000000000000000000000000000000000000000000000000000002
+			0000000000000000000000000000000000000000000000000000000000000007
+			0000000000000000000000000000000000000000000000000000000000000040
+			0000000000000000000000000000000000000000000000000000000000000004
+			6461766500000000000000000000000000000000000000000000000000000000
+		"
+        );
+        let mut stream = Stream::new(&encoded);
+        assert_eq!(stream.pop::<(u64, String)>().unwrap(), (7, "dave".to_owned()));
+        let kind = stream.pop::<[bool; 2]>().unwrap_err();
+        let err = stream.error(kind).argument(1, "bool[2]");
+        assert_eq!(
+            err,
+            DecodeError {
+                kind: Error::InvalidBool,
+                offset: 64,
+                argument: Some(1),
+                expected: Some("bool[2]".to_owned()),
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            "invalid bool at offset 64 in argument 1 of type bool[2]"
+        );
+
+        // offsets of nested values are relative to the whole payload
+        let mut encoded = encoded.to_vec();
+        encoded[0xbf] = 0x40;
+        let mut stream = Stream::new(&encoded);
+        let kind = stream.pop::<(u64, String)>().unwrap_err();
+        assert_eq!(stream.error(kind).to_string(), "unexpected end of payload at offset 192");
+
+        let params = [ParamType::Tuple(vec![ParamType::Uint(64), ParamType::String])];
+        let err = decode_values(&params, &encoded).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "unexpected end of payload at offset 192 in argument 0 of type (uint64,string)"
+        );
+    }
+
+    #[test]
+    fn strict_trailing_bytes() {
+        let encoded = hex!(
+            "
+			0000000000000000000000000000000000000000000000000000000000000001
+			0000000000000000000000000000000000000000000000000000000000000000
+		"
+        );
+
+        let mut stream = Stream::new(&encoded);
+        assert_eq!(stream.pop::<u64>().unwrap(), 1);
+        stream.finish().unwrap();
+
+        let mut stream = Stream::strict(&encoded);
+        assert_eq!(stream.pop::<u64>().unwrap(), 1);
+        assert_eq!(stream.finish().unwrap_err(), Error::TrailingBytes);
+    }
+
+    #[test]
+    fn nested_dynamic_arrays() {
+        // example of `g(uint256[][],string[])` from the Solidity ABI specification
+        let encoded = hex!(
+            "
+			0000000000000000000000000000000000000000000000000000000000000040
+			0000000000000000000000000000000000000000000000000000000000000140
+			0000000000000000000000000000000000000000000000000000000000000002
+			0000000000000000000000000000000000000000000000000000000000000040
+			00000000000000000000000000000000000000000000000000000000000000a0
+			0000000000000000000000000000000000000000000000000000000000000002
+			0000000000000000000000000000000000000000000000000000000000000001
+			0000000000000000000000000000000000000000000000000000000000000002
+			0000000000000000000000000000000000000000000000000000000000000001
+			0000000000000000000000000000000000000000000000000000000000000003
+			0000000000000000000000000000000000000000000000000000000000000003
+			0000000000000000000000000000000000000000000000000000000000000060
+			00000000000000000000000000000000000000000000000000000000000000a0
+			00000000000000000000000000000000000000000000000000000000000000e0
+			0000000000000000000000000000000000000000000000000000000000000003
+			6f6e650000000000000000000000000000000000000000000000000000000000
+			0000000000000000000000000000000000000000000000000000000000000003
+			74776f0000000000000000000000000000000000000000000000000000000000
+			0000000000000000000000000000000000000000000000000000000000000005
+			7468726565000000000000000000000000000000000000000000000000000000
+		"
+        );
+        let numbers = vec![vec![U256::from(1), U256::from(2)], vec![U256::from(3)]];
+        let strings = vec!["one".to_owned(), "two".to_owned(), "three".to_owned()];
+
+        let mut sink = Sink::new(2);
+        sink.push(numbers.clone());
+        sink.push(strings.clone());
+        assert_eq!(sink.finalize_panicking(), encoded.to_vec());
+
+        let mut stream = Stream::strict(&encoded);
+        assert_eq!(stream.pop::<Vec<Vec<U256>>>().unwrap(), numbers);
+        assert_eq!(stream.pop::<Vec<String>>().unwrap(), strings);
+        stream.finish().unwrap();
+    }
+
+    #[test]
+    fn array_of_dynamic_tuples() {
+        let encoded = hex!(
+            "
+			0000000000000000000000000000000000000000000000000000000000000020
+			0000000000000000000000000000000000000000000000000000000000000002
+			0000000000000000000000000000000000000000000000000000000000000040
+			00000000000000000000000000000000000000000000000000000000000000c0
+			0000000000000000000000000000000000000000000000000000000000000001
+			0000000000000000000000000000000000000000000000000000000000000040
+			0000000000000000000000000000000000000000000000000000000000000001
+			6100000000000000000000000000000000000000000000000000000000000000
+			0000000000000000000000000000000000000000000000000000000000000002
+			0000000000000000000000000000000000000000000000000000000000000040
+			0000000000000000000000000000000000000000000000000000000000000002
+			6263000000000000000000000000000000000000000000000000000000000000
+		"
+        );
+        let value = vec![(U256::from(1), "a".to_owned()), (U256::from(2), "bc".to_owned())];
+
+        let mut sink = Sink::new(1);
+        sink.push(value.clone());
+        assert_eq!(sink.finalize_panicking(), encoded.to_vec());
+
+        let mut stream = Stream::strict(&encoded);
+        assert_eq!(stream.pop::<Vec<(U256, String)>>().unwrap(), value);
+        stream.finish().unwrap();
+
+        let param = ParamType::Array(Box::new(ParamType::Tuple(vec![
+            ParamType::Uint(256),
+            ParamType::String,
+        ])));
+        let values = decode_values(&[param], &encoded).unwrap();
+        assert_eq!(encode_values(values), encoded.to_vec());
+    }
+
+    #[test]
+    fn three_levels_of_nesting() {
+        // `string[][]` holding `[["a"], ["b", "c"]]`
+        let encoded = hex!(
+            "
+			0000000000000000000000000000000000000000000000000000000000000020
+			0000000000000000000000000000000000000000000000000000000000000002
+			0000000000000000000000000000000000000000000000000000000000000040
+			00000000000000000000000000000000000000000000000000000000000000c0
+			0000000000000000000000000000000000000000000000000000000000000001
+			0000000000000000000000000000000000000000000000000000000000000020
+			0000000000000000000000000000000000000000000000000000000000000001
+			6100000000000000000000000000000000000000000000000000000000000000
+			0000000000000000000000000000000000000000000000000000000000000002
+			0000000000000000000000000000000000000000000000000000000000000040
+			0000000000000000000000000000000000000000000000000000000000000080
+			0000000000000000000000000000000000000000000000000000000000000001
+			6200000000000000000000000000000000000000000000000000000000000000
+			0000000000000000000000000000000000000000000000000000000000000001
+			6300000000000000000000000000000000000000000000000000000000000000
+		"
+        );
+        let value = vec![
+            vec!["a".to_owned()],
+            vec!["b".to_owned(), "c".to_owned()],
+        ];
+
+        let mut sink = Sink::new(1);
+        sink.push(value.clone());
+        assert_eq!(sink.finalize_panicking(), encoded.to_vec());
+
+        let mut stream = Stream::strict(&encoded);
+        assert_eq!(stream.pop::<Vec<Vec<String>>>().unwrap(), value);
+        stream.finish().unwrap();
+
+        let mut buffer = [0u8; 15 * 32];
+        assert_eq!(encode_into(&value, &mut buffer), Ok(encoded.len()));
+        assert_eq!(&buffer[..], &encoded[..]);
+    }
+
+    fn mixed_params() -> Vec<ParamType> {
+        vec![
+            ParamType::Uint(256),
+            ParamType::Bytes,
+            ParamType::String,
+            ParamType::FixedBytes(4),
+            ParamType::Array(Box::new(ParamType::Uint(256))),
+            ParamType::Tuple(vec![ParamType::Uint(64), ParamType::String]),
+            ParamType::FixedArray(Box::new(ParamType::String), 2),
+            ParamType::Array(Box::new(ParamType::String)),
+            ParamType::Bool,
+            ParamType::Address,
+            ParamType::Int(32),
+        ]
+    }
+
+    fn mixed_values() -> Vec<AbiValue> {
+        vec![
+            AbiValue::Uint(U256::from(69)),
+            AbiValue::Bytes(vec![0x12, 0x34]),
+            AbiValue::String("dave".to_owned()),
+            AbiValue::FixedBytes(vec![0xde, 0xad, 0xbe, 0xef]),
+            AbiValue::Array(vec![AbiValue::Uint(U256::from(1)), AbiValue::Uint(U256::from(2))]),
+            AbiValue::Tuple(vec![AbiValue::Uint(U256::from(7)), AbiValue::String("eve".to_owned())]),
+            AbiValue::FixedArray(vec![
+                AbiValue::String("a".to_owned()),
+                AbiValue::String("b".to_owned()),
+            ]),
+            AbiValue::Array(vec![
+                AbiValue::String("x".to_owned()),
+                AbiValue::String("yz".to_owned()),
+            ]),
+            AbiValue::Bool(true),
+            AbiValue::Address(Address::from([0x11u8; 20])),
+            AbiValue::Int(I256::from(-42i64)),
+        ]
+    }
+
+    #[test]
+    fn runtime_values_match_typed() {
+        let mut sink = Sink::new(
+            U256::HEAD_WORDS
+                + <Vec<u8>>::HEAD_WORDS
+                + String::HEAD_WORDS
+                + <[u8; 4]>::HEAD_WORDS
+                + <Vec<U256>>::HEAD_WORDS
+                + <(u64, String)>::HEAD_WORDS
+                + <[String; 2]>::HEAD_WORDS
+                + <Vec<String>>::HEAD_WORDS
+                + bool::HEAD_WORDS
+                + Address::HEAD_WORDS
+                + i32::HEAD_WORDS,
+        );
+        sink.push(U256::from(69));
+        sink.push(vec![0x12u8, 0x34]);
+        sink.push("dave".to_owned());
+        sink.push([0xdeu8, 0xad, 0xbe, 0xef]);
+        sink.push(vec![U256::from(1), U256::from(2)]);
+        sink.push((7u64, "eve".to_owned()));
+        sink.push(["a".to_owned(), "b".to_owned()]);
+        sink.push(vec!["x".to_owned(), "yz".to_owned()]);
+        sink.push(true);
+        sink.push(Address::from([0x11u8; 20]));
+        sink.push(-42i32);
+        let typed = sink.finalize_panicking();
+
+        assert_eq!(encode_values(mixed_values()), typed);
+        assert_eq!(decode_values(&mixed_params(), &typed).unwrap(), mixed_values());
+    }
+
+    #[test]
+    fn runtime_values_stream() {
+        let encoded = encode_values(mixed_values());
+        let mut stream = Stream::strict(&encoded);
+        for (param, value) in mixed_params().iter().zip(mixed_values()) {
+            assert_eq!(stream.pop_value(param).unwrap(), value);
+        }
+        stream.finish().unwrap();
+    }
+
+    #[test]
+    fn runtime_values_overflow() {
+        let encoded = hex!("0000000000000000000000000000000000000000000000000000000000000100");
+        assert_eq!(
+            decode_values(&[ParamType::Uint(8)], &encoded).unwrap_err().kind,
+            Error::IntegerOverflow
+        );
+        assert_eq!(
+            decode_values(&[ParamType::Uint(16)], &encoded).unwrap(),
+            vec![AbiValue::Uint(U256::from(0x100))]
+        );
+
+        let encoded = hex!("ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f");
+        assert_eq!(
+            decode_values(&[ParamType::Int(8)], &encoded).unwrap_err().kind,
+            Error::IntegerOverflow
+        );
+        assert_eq!(
+            decode_values(&[ParamType::Int(16)], &encoded).unwrap(),
+            vec![AbiValue::Int(I256::from(-129i64))]
+        );
+    }
+
+    #[test]
+    fn runtime_values_invalid_types() {
+        let encoded = [0u8; 64];
+        let huge = ParamType::FixedArray(Box::new(ParamType::Bool), usize::MAX / 2);
+        let invalid = [
+            ParamType::Int(0),
+            ParamType::Uint(7),
+            ParamType::Uint(264),
+            ParamType::FixedBytes(0),
+            ParamType::FixedBytes(33),
+            ParamType::FixedArray(Box::new(huge.clone()), 4),
+            huge,
+        ];
+        for param in invalid.iter() {
+            assert_eq!(param.validate(), Err(Error::InvalidParamType));
+            assert_eq!(
+                decode_values(&[param.clone()], &encoded).unwrap_err().kind,
+                Error::InvalidParamType
+            );
+        }
+        for param in mixed_params().iter() {
+            assert_eq!(param.validate(), Ok(()));
+        }
+
+        let annotated = annotate(&encoded, &[ParamType::Bool, ParamType::Int(0)]);
+        assert!(annotated.ends_with("note: arg1 has invalid type int0\n"));
+    }
+
+    #[test]
+    fn param_type_display() {
+        let names: Vec<String> = mixed_params().iter().map(|param| param.to_string()).collect();
+        assert_eq!(
+            names.join(","),
+            "uint256,bytes,string,bytes4,uint256[],(uint64,string),string[2],string[],bool,address,\
+             int32"
+        );
+    }
+
+    #[test]
+    fn packed() {
+        // example from the Solidity documentation of `abi.encodePacked`
+        let encoded = encode_packed((-1i16, [0x42u8], 0x03u16, "Hello, world!"));
+        assert_eq!(encoded, hex!("ffff42000348656c6c6f2c20776f726c6421").to_vec());
+
+        let encoded = encode_packed((
+            Address::from([0x11u8; 20]),
+            true,
+            Uint::<24>::new(U256::from(0x0a0b0c)).unwrap(),
+            Int::<24>::new(I256::from(-2i64)).unwrap(),
+            vec![0x12u8, 0x34],
+            "ab".to_owned(),
+        ));
+        assert_eq!(
+            encoded,
+            hex!("1111111111111111111111111111111111111111010a0b0cfffffe12346162").to_vec()
+        );
+    }
+
+    #[test]
+    fn packed_arrays() {
+        // members of arrays are padded to 32 bytes, there is no length
+        let mut sink = PackedSink::new();
+        sink.push(vec![1u16, 2]);
+        sink.push([true]);
+        sink.push([[0xabu8; 4]]);
+        assert_eq!(
+            sink.finalize(),
+            hex!(
+                "
+				0000000000000000000000000000000000000000000000000000000000000001
+				0000000000000000000000000000000000000000000000000000000000000002
+				0000000000000000000000000000000000000000000000000000000000000001
+				abababab00000000000000000000000000000000000000000000000000000000
+			"
+            )
+            .to_vec()
         );
-        let mut stream = Stream::new(&encoded);
+    }
 
-        let bytes: Vec<u8> = stream.pop().unwrap();
+    #[test]
+    fn buffer_matches_sink() {
+        let mut sink = Sink::new(5);
+        sink.push(U256::from(69));
+        sink.push("dave".to_owned());
+        sink.push((7u64, vec![0x12u8, 0x34]));
+        sink.push([true, false]);
+        let expected = sink.finalize_panicking();
 
-        assert_eq!(vec![0x12u8, 0x34], bytes);
+        let mut buffer = [0xffu8; 512];
+        let mut sink = BufferSink::new(&mut buffer, 5).unwrap();
+        sink.push(&U256::from(69)).unwrap();
+        sink.push("dave").unwrap();
+        sink.push(&(7u64, &[0x12u8, 0x34][..])).unwrap();
+        sink.push(&[true, false]).unwrap();
+        let len = sink.finalize().unwrap();
+
+        assert_eq!(&buffer[..len], &expected[..]);
     }
 
     #[test]
-    fn two_bytes() {
+    fn buffer_nested() {
         let encoded = hex!(
             "
+			0000000000000000000000000000000000000000000000000000000000000020
+			0000000000000000000000000000000000000000000000000000000000000002
 			0000000000000000000000000000000000000000000000000000000000000040
 			0000000000000000000000000000000000000000000000000000000000000080
-			000000000000000000000000000000000000000000000000000000000000001f
-			1000000000000000000000000000000000000000000000000000000000000200
-			0000000000000000000000000000000000000000000000000000000000000020
-			0010000000000000000000000000000000000000000000000000000000000002
+			0000000000000000000000000000000000000000000000000000000000000001
+			6100000000000000000000000000000000000000000000000000000000000000
+			0000000000000000000000000000000000000000000000000000000000000002
+			6263000000000000000000000000000000000000000000000000000000000000
 		"
         );
+        let mut buffer = [0u8; 256];
+        let len = encode_into(&["a", "bc"][..], &mut buffer).unwrap();
+        assert_eq!(&buffer[..len], &encoded[..]);
+    }
 
-        let mut stream = Stream::new(&encoded);
+    #[test]
+    fn buffer_overflow() {
+        let mut buffer = [0u8; 95];
+        assert_eq!(encode_into("dave", &mut buffer).unwrap_err(), BufferOverflow);
+        assert_eq!(encode_into(&[1u64, 2, 3], &mut buffer).unwrap_err(), BufferOverflow);
+        assert_eq!(BufferSink::new(&mut buffer, 3).err(), Some(BufferOverflow));
 
-        let bytes1: Vec<u8> = stream.pop().unwrap();
-        let bytes2: Vec<u8> = stream.pop().unwrap();
+        let mut buffer = [0u8; 96];
+        assert_eq!(encode_into("dave", &mut buffer), Ok(96));
 
+        let mut sink = BufferSink::new(&mut buffer, 2).unwrap();
+        sink.push(&1u64).unwrap();
         assert_eq!(
-            bytes1,
-            hex!("10000000000000000000000000000000000000000000000000000000000002")
-        );
-        assert_eq!(
-            bytes2,
-            hex!("0010000000000000000000000000000000000000000000000000000000000002")
+            sink.finalize().unwrap_err(),
+            CapacityMismatch {
+                expected: 2,
+                actual: 1
+            }
         );
     }
 
     #[test]
-    fn bytes_encode() {
+    fn signature_canonical() {
+        let signature = Signature::parse("transfer(address,uint256)").unwrap();
+        assert_eq!(signature.name, "transfer");
+        assert_eq!(signature.inputs, vec![ParamType::Address, ParamType::Uint(256)]);
+        assert!(signature.outputs.is_empty());
+        assert_eq!(signature.selector(), 0xa9059cbb);
+    }
+
+    #[test]
+    fn signature_declaration() {
+        let signature: Signature = "function balanceOf(address owner) external view returns (uint256)"
+            .parse()
+            .unwrap();
+        assert_eq!(signature.canonical(), "balanceOf(address)");
+        assert_eq!(signature.outputs, vec![ParamType::Uint(256)]);
+        assert_eq!(signature.selector(), 0x70a08231);
+
+        let signature = Signature::parse(
+            "function f(tuple(uint a, string[] memory b)[2] calldata x, bytes32, int) returns (bool ok)",
+        )
+        .unwrap();
+        assert_eq!(signature.canonical(), "f((uint256,string[])[2],bytes32,int256)");
+        assert_eq!(signature.selector(), 0x60dbb925);
+    }
+
+    #[test]
+    fn signature_param_type() {
         assert_eq!(
-            super::single_encode(vec![0x12u8, 0x34]),
-            hex!(
-                "
-				0000000000000000000000000000000000000000000000000000000000000020
-				0000000000000000000000000000000000000000000000000000000000000002
-				1234000000000000000000000000000000000000000000000000000000000000
-			"
-            )
-            .to_vec()
+            "uint256[][3]".parse::<ParamType>().unwrap(),
+            ParamType::FixedArray(Box::new(ParamType::Array(Box::new(ParamType::Uint(256)))), 3)
+        );
+        assert_eq!(
+            "(address,byte)[]".parse::<ParamType>().unwrap(),
+            ParamType::Array(Box::new(ParamType::Tuple(vec![
+                ParamType::Address,
+                ParamType::FixedBytes(1),
+            ])))
         );
     }
 
+    #[test]
+    fn signature_invalid() {
+        assert_eq!(Signature::parse("").unwrap_err(), SignatureError::InvalidName);
+        assert_eq!(Signature::parse("1f()").unwrap_err(), SignatureError::InvalidName);
+        assert_eq!(Signature::parse("f").unwrap_err(), SignatureError::UnexpectedToken);
+        assert_eq!(Signature::parse("f(uint256").unwrap_err(), SignatureError::UnexpectedToken);
+        assert_eq!(Signature::parse("f() returns").unwrap_err(), SignatureError::UnexpectedToken);
+        assert_eq!(Signature::parse("f() mutable").unwrap_err(), SignatureError::UnexpectedToken);
+        assert_eq!(Signature::parse("f(uint7)").unwrap_err(), SignatureError::InvalidType);
+        assert_eq!(Signature::parse("f(uint264)").unwrap_err(), SignatureError::InvalidType);
+        assert_eq!(Signature::parse("f(bytes33)").unwrap_err(), SignatureError::InvalidType);
+        assert_eq!(Signature::parse("f(int08)").unwrap_err(), SignatureError::InvalidType);
+        assert_eq!(Signature::parse("f(foo)").unwrap_err(), SignatureError::InvalidType);
+        assert_eq!(Signature::parse("f(bool[x])").unwrap_err(), SignatureError::InvalidType);
+    }
+
+    #[test]
+    fn signature_too_deep() {
+        let nested = |depth| format!("f({}uint8{})", "(".repeat(depth), ")".repeat(depth));
+        assert!(Signature::parse(&nested(64)).is_ok());
+        assert_eq!(Signature::parse(&nested(65)).unwrap_err(), SignatureError::TooDeep);
+        assert_eq!(Signature::parse(&nested(100_000)).unwrap_err(), SignatureError::TooDeep);
+
+        let array = |dims| format!("(uint8{})", "[]".repeat(dims));
+        assert!(array(63).parse::<ParamType>().is_ok());
+        assert_eq!(array(64).parse::<ParamType>().unwrap_err(), SignatureError::TooDeep);
+        assert_eq!(array(100_000).parse::<ParamType>().unwrap_err(), SignatureError::TooDeep);
+    }
 }
 
 #[cfg(feature = "std")]
@@ -138,6 +1026,23 @@ fn u32_encode() {
     );
 }
 
+#[test]
+fn finalize_capacity_mismatch() {
+    let mut sink = super::Sink::new(2);
+    sink.push(69u32);
+    assert_eq_core!(
+        sink.finalize(),
+        Err(super::CapacityMismatch {
+            expected: 2,
+            actual: 1
+        })
+    );
+
+    let mut sink = super::Sink::new(1);
+    sink.push(vec![69u32]);
+    assert_eq_core!(sink.finalize().map(|result| result.len()), Ok(3 * 32));
+}
+
 #[test]
 fn sample1_decode() {
     let payload: &[u8] = &[
@@ -221,6 +1126,308 @@ fn sample2_decode() {
     assert_eq_core!(v3, vec![U256::from(1), U256::from(2), U256::from(3)]);
 }
 
+#[test]
+fn static_tuple() {
+    let payload: &[u8] = &[
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x45, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x01,
+    ];
+
+    let tuple = single_decode::<(u32, bool)>(payload);
+    assert_eq!(tuple, (69, true));
+
+    let mut sink = Sink::new(2);
+    sink.push(tuple);
+    assert_eq!(&sink.finalize_panicking()[..], payload);
+}
+
+#[test]
+fn tuple_layout() {
+    assert!(<(u32, bool)>::IS_FIXED);
+    assert_eq!(<(u32, bool)>::HEAD_WORDS, 2);
+    assert_eq!(<(u32, (u64, bool))>::HEAD_WORDS, 3);
+    assert!(!<(u32, Vec<u8>)>::IS_FIXED);
+    assert_eq!(<(u32, Vec<u8>)>::HEAD_WORDS, 1);
+}
+
+#[test]
+fn fixed_array() {
+    let array = [U256::from(1), U256::from(2), U256::from(3)];
+
+    let mut sink = Sink::new(3);
+    sink.push(array);
+    let payload = sink.finalize_panicking();
+
+    assert_eq!(payload.len(), 96);
+    assert_eq!(payload[31], 1);
+    assert_eq!(payload[63], 2);
+    assert_eq!(payload[95], 3);
+
+    assert_eq_core!(single_decode::<[U256; 3]>(&payload), array);
+}
+
+#[test]
+fn fixed_array_layout() {
+    assert!(<[Address; 3]>::IS_FIXED);
+    assert_eq!(<[Address; 3]>::HEAD_WORDS, 3);
+    assert_eq!(<[[u32; 2]; 3]>::HEAD_WORDS, 6);
+    assert!(!<[Vec<u8>; 3]>::IS_FIXED);
+    assert_eq!(<[Vec<u8>; 3]>::HEAD_WORDS, 1);
+}
+
+#[test]
+fn u8_encode() {
+    let payload = single_encode(0xffu8);
+    assert_eq!(&payload[..31], &[0u8; 31][..]);
+    assert_eq!(payload[31], 0xff);
+    assert_eq!(single_decode::<u8>(&payload), 0xff);
+}
+
+#[test]
+fn u128_encode() {
+    let x: u128 = u128::max_value();
+    let payload = single_encode(x);
+    assert_eq!(&payload[..16], &[0u8; 16][..]);
+    assert_eq!(&payload[16..], &[0xffu8; 16][..]);
+    assert_eq!(single_decode::<u128>(&payload), x);
+}
+
+#[test]
+fn negative_small_ints() {
+    assert_eq!(&single_encode(-1i8)[..], &[0xff; 32][..]);
+    assert_eq!(single_decode::<i8>(&[0xff; 32]), -1);
+    assert_eq!(single_decode::<i16>(&[0xff; 32]), -1);
+    assert_eq!(single_decode::<i128>(&[0xff; 32]), -1);
+
+    let x: i128 = i128::min_value();
+    assert_eq!(single_decode::<i128>(&single_encode(x)), x);
+}
+
+#[test]
+fn padding_test_small_ints() {
+    let mut sample = [0u8; 32];
+    sample[30] = 1;
+    let mut stream = ::eth::Stream::new(&sample);
+    assert_eq!(stream.pop::<u8>().unwrap_err(), Error::InvalidPadding);
+
+    // positive i16 with sign extended padding
+    let mut sample = [0xff; 32];
+    sample[30] = 0x7f;
+    let mut stream = ::eth::Stream::new(&sample);
+    assert_eq!(stream.pop::<i16>().unwrap_err(), Error::InvalidPadding);
+
+    // i8 does not fit
+    let mut sample = [0u8; 32];
+    sample[31] = 0x80;
+    let mut stream = ::eth::Stream::new(&sample);
+    assert_eq!(stream.pop::<i8>().unwrap_err(), Error::InvalidPadding);
+}
+
+#[test]
+fn u8_arrays_are_bytes() {
+    assert!(<[u8; 4]>::IS_FIXED);
+    assert_eq!(<[u8; 4]>::HEAD_WORDS, 1);
+
+    let payload = single_encode([1u8, 2, 3, 4]);
+    assert_eq!(&payload[..4], &[1, 2, 3, 4]);
+    assert_eq!(&payload[4..], &[0u8; 28][..]);
+}
+
+#[test]
+fn long_u8_arrays_are_uint8_arrays() {
+    // `[u8; 33]` is `uint8[33]`, with one word per member
+    assert!(<[u8; 33]>::IS_FIXED);
+    assert_eq!(<[u8; 33]>::HEAD_WORDS, 33);
+
+    let mut value = [0u8; 33];
+    value[0] = 1;
+    value[32] = 2;
+    let mut sink = Sink::new(33);
+    sink.push(value);
+    let payload = sink.finalize_panicking();
+    assert_eq!(payload.len(), 33 * 32);
+    assert_eq!(payload[31], 1);
+    assert_eq!(payload[33 * 32 - 1], 2);
+    assert_eq!(payload.iter().filter(|byte| **byte != 0).count(), 2);
+    assert_eq!(single_decode::<[u8; 33]>(&payload)[..], value[..]);
+
+    let mut buffer = [0u8; 33 * 32];
+    assert_eq!(encode_into(&value, &mut buffer), Ok(33 * 32));
+    assert_eq!(&buffer[..], &payload[..]);
+
+    assert_eq!(encode_packed(value), payload);
+    assert_eq!(AsLog::as_log(&value), util::keccak(&payload));
+}
+
+#[test]
+fn canonical() {
+    fn check<T: AbiType>(expected: &str) {
+        assert_eq!(T::CANONICAL.to_string(), expected);
+        assert_eq!(&T::CANONICAL.hash()[..], util::keccak(expected.as_bytes()).as_ref());
+    }
+
+    check::<u8>("uint8");
+    check::<i128>("int128");
+    check::<Uint<24>>("uint24");
+    check::<H256>("uint256");
+    check::<Vec<u8>>("bytes");
+    check::<[u8; 32]>("bytes32");
+    check::<[u8; 33]>("uint8[33]");
+    check::<Vec<[u16; 2]>>("uint16[2][]");
+    check::<(Address, (String, bool))>("(address,(string,bool))");
+
+    const TRANSFER: Canonical = Canonical::Signature {
+        name: "transfer",
+        params: &[<Address as AbiType>::CANONICAL, <U256 as AbiType>::CANONICAL],
+    };
+    const SELECTOR: u32 = TRANSFER.selector();
+    assert_eq!(SELECTOR, 0xa905_9cbb);
+}
+
+#[test]
+fn json_abi_entries() {
+    const NOTE: Canonical = Canonical::Tuple {
+        members: &[<u64 as AbiType>::CANONICAL, <String as AbiType>::CANONICAL],
+        names: &["id", "text"],
+    };
+    const NOTES: Canonical = Canonical::FixedArray {
+        elem: &Canonical::Array {
+            elem: &NOTE,
+            is_byte: false,
+        },
+        len: 2,
+        is_byte: false,
+    };
+
+    let abi = json_abi(&[
+        JsonEntry::Function {
+            name: "annotate",
+            inputs: &[
+                JsonParam {
+                    name: "notes",
+                    canonical: NOTES,
+                    indexed: false,
+                },
+                JsonParam {
+                    name: "hash",
+                    canonical: <[u8; 32] as AbiType>::CANONICAL,
+                    indexed: false,
+                },
+            ],
+            outputs: &[],
+            constant: true,
+        },
+        JsonEntry::Event {
+            name: "Annotated",
+            inputs: &[JsonParam {
+                name: "pair",
+                canonical: <(Address, Vec<u8>) as AbiType>::CANONICAL,
+                indexed: true,
+            }],
+        },
+        JsonEntry::Constructor { inputs: &[] },
+    ]);
+
+    assert_eq!(
+        abi,
+        r#"[
+  {
+    "type": "function",
+    "name": "annotate",
+    "inputs": [
+      {
+        "name": "notes",
+        "type": "tuple[][2]",
+        "components": [
+          {
+            "name": "id",
+            "type": "uint64"
+          },
+          {
+            "name": "text",
+            "type": "string"
+          }
+        ]
+      },
+      {
+        "name": "hash",
+        "type": "bytes32"
+      }
+    ],
+    "outputs": [],
+    "constant": true
+  },
+  {
+    "type": "event",
+    "name": "Annotated",
+    "inputs": [
+      {
+        "name": "pair",
+        "type": "tuple",
+        "components": [
+          {
+            "name": "",
+            "type": "address"
+          },
+          {
+            "name": "",
+            "type": "bytes"
+          }
+        ],
+        "indexed": true
+      }
+    ]
+  },
+  {
+    "type": "constructor",
+    "inputs": []
+  }
+]"#
+    );
+}
+
+#[test]
+fn negative_i256() {
+    let x = I256::from(-2i64);
+    let payload = single_encode(x);
+
+    assert_eq!(&payload[..31], &[0xff; 31][..]);
+    assert_eq!(payload[31], 0xfe);
+    assert_eq_core!(single_decode::<I256>(&payload), x);
+}
+
+#[test]
+fn bounded_ints() {
+    let x = Uint::<24>::max_value();
+    let payload = single_encode(x);
+    assert_eq!(&payload[..29], &[0u8; 29][..]);
+    assert_eq!(&payload[29..], &[0xff; 3][..]);
+    assert_eq_core!(single_decode::<Uint<24>>(&payload), x);
+
+    let y = Int::<72>::min_value();
+    let payload = single_encode(y);
+    assert_eq!(&payload[..23], &[0xff; 23][..]);
+    assert_eq!(&payload[23..], &[0x80, 0, 0, 0, 0, 0, 0, 0, 0][..]);
+    assert_eq_core!(single_decode::<Int<72>>(&payload), y);
+}
+
+#[test]
+fn bounded_ints_overflow() {
+    let mut sample = [0u8; 32];
+    sample[28] = 1;
+    let mut stream = ::eth::Stream::new(&sample);
+    assert_eq!(stream.pop::<Uint<24>>().unwrap_err(), Error::IntegerOverflow);
+
+    // positive int72 with the sign bit set
+    let mut sample = [0u8; 32];
+    sample[23] = 0x80;
+    let mut stream = ::eth::Stream::new(&sample);
+    assert_eq!(stream.pop::<Int<72>>().unwrap_err(), Error::IntegerOverflow);
+}
+
 #[test]
 fn negative_i32() {
     let x: i32 = -1;
@@ -271,3 +1478,266 @@ fn padding_test_i64() {
     let mut stream = ::eth::Stream::new(&sample);
     assert_eq!(stream.pop::<i64>().unwrap_err(), Error::InvalidPadding);
 }
+
+#[test]
+fn error_revert() {
+    // revert reason of `require(msg.value >= price, "Not enough Ether provided.")`
+    let payload = hex!(
+        "08c379a0"
+        "0000000000000000000000000000000000000000000000000000000000000020"
+        "000000000000000000000000000000000000000000000000000000000000001a"
+        "4e6f7420656e6f7567682045746865722070726f76696465642e000000000000"
+    );
+    assert_eq!(encode_error("Not enough Ether provided."), &payload[..]);
+    assert_eq!(
+        encode_error(&format_args!("Not enough {} provided.", "Ether")),
+        &payload[..]
+    );
+    assert_eq!(
+        decode_revert(&payload),
+        Ok(RevertReason::Error("Not enough Ether provided.".into()))
+    );
+}
+
+#[test]
+fn panic_revert() {
+    // arithmetic overflow
+    let payload = hex!(
+        "4e487b71"
+        "0000000000000000000000000000000000000000000000000000000000000011"
+    );
+    assert_eq!(encode_panic(U256::from(0x11)), &payload[..]);
+    assert_eq!(
+        ContractError::encode(RevertReason::Panic(U256::from(0x11))),
+        &payload[..]
+    );
+    assert_eq!(decode_revert(&payload), Ok(RevertReason::Panic(U256::from(0x11))));
+    assert_eq!(
+        <RevertReason as ContractError>::decode(&payload),
+        Ok(RevertReason::Panic(U256::from(0x11)))
+    );
+    assert_eq!(
+        RevertReason::Panic(U256::from(0x11)).to_string(),
+        "panic code 0x11"
+    );
+}
+
+#[test]
+fn unknown_revert() {
+    assert_eq!(decode_revert(&[]), Err(Error::UnknownSelector));
+    assert_eq!(
+        decode_revert(&hex!("a9059cbb")),
+        Err(Error::UnknownSelector)
+    );
+    assert_eq!(
+        decode_revert(&hex!("4e487b71")),
+        Err(Error::UnexpectedEof)
+    );
+}
+
+#[test]
+fn revert() {
+    let mismatch = CapacityMismatch { expected: 2, actual: 1 };
+    let revert = Revert::error(&format_args!("return value encoding failed: {}", mismatch));
+    assert_eq!(
+        revert.payload(),
+        &encode_error("return value encoding failed: expected 2 words to be pushed to the sink, got 1")[..]
+    );
+    assert_eq!(
+        revert.to_string(),
+        "return value encoding failed: expected 2 words to be pushed to the sink, got 1"
+    );
+
+    let revert = Revert::from(RevertReason::Panic(U256::from(0x11)));
+    assert_eq!(revert.payload(), &encode_panic(U256::from(0x11))[..]);
+    assert_eq!(revert.reason(), Ok(RevertReason::Panic(U256::from(0x11))));
+
+    let revert = Revert::new(hex!("cf479181").to_vec());
+    assert_eq!(revert.reason(), Err(Error::UnknownSelector));
+    assert_eq!(revert.to_string(), "revert payload 0xcf479181");
+}
+
+#[cfg(feature = "serde")]
+mod serde_format {
+    use super::super::*;
+    use lib::*;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Transfer {
+        id: u64,
+        memo: String,
+        payload: Vec<u8>,
+        tag: [u8; 4],
+        amounts: Vec<u32>,
+        delta: i32,
+        approved: bool,
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Point(u32, u32);
+
+    #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+    enum Color {
+        Red,
+        Green,
+        Blue,
+    }
+
+    fn encode<T: AbiType>(val: T) -> Vec<u8> {
+        let mut sink = Sink::new(T::HEAD_WORDS);
+        sink.push(val);
+        sink.finalize_panicking()
+    }
+
+    fn transfer() -> Transfer {
+        Transfer {
+            id: 7,
+            memo: "hello".to_owned(),
+            payload: vec![1, 2, 3],
+            tag: [0xde, 0xad, 0xbe, 0xef],
+            amounts: vec![10, 20],
+            delta: -5,
+            approved: true,
+        }
+    }
+
+    #[test]
+    fn serde_matches_abi_type() {
+        let value = transfer();
+        let expected = encode((
+            value.id,
+            value.memo.clone(),
+            value.payload.clone(),
+            value.tag,
+            value.amounts.clone(),
+            value.delta,
+            value.approved,
+        ));
+        let encoded = to_bytes(&value).unwrap();
+        assert_eq!(encoded, expected);
+        assert_eq!(from_bytes::<Transfer>(&encoded).unwrap(), value);
+
+        let point = Point(1, 2);
+        let encoded = to_bytes(&point).unwrap();
+        assert_eq!(encoded, encode((1u32, 2u32)));
+        assert_eq!(from_bytes::<Point>(&encoded).unwrap(), point);
+    }
+
+    #[test]
+    fn serde_nested() {
+        let value = (vec![Point(1, 2), Point(3, 4)], vec![Color::Blue, Color::Red]);
+        let encoded = to_bytes(&value).unwrap();
+        assert_eq!(
+            encoded,
+            encode((vec![(1u32, 2u32), (3, 4)], vec![2u32, 0]))
+        );
+        assert_eq!(from_bytes::<(Vec<Point>, Vec<Color>)>(&encoded).unwrap(), value);
+
+        let encoded = to_bytes(&Color::Green).unwrap();
+        assert_eq!(encoded, encode(1u32));
+        assert_eq!(from_bytes::<Color>(&encoded).unwrap(), Color::Green);
+    }
+
+    #[test]
+    fn serde_byte_tuples() {
+        // tuples of `u8` are tuples of `uint8`, only arrays are `bytesN`
+        let encoded = to_bytes(&(1u8, 2u8)).unwrap();
+        assert_eq!(encoded, encode((1u8, 2u8)));
+        assert_eq!(from_bytes::<(u8, u8)>(&encoded).unwrap(), (1, 2));
+
+        let encoded = to_bytes(&[1u8, 2]).unwrap();
+        assert_eq!(encoded, encode([1u8, 2]));
+        assert_eq!(from_bytes::<[u8; 2]>(&encoded).unwrap(), [1, 2]);
+
+        let value = (vec![(3u8, 4u8)], [5u8; 2]);
+        let encoded = to_bytes(&value).unwrap();
+        assert_eq!(encoded, encode(value.clone()));
+        assert_eq!(from_bytes::<(Vec<(u8, u8)>, [u8; 2])>(&encoded).unwrap(), value);
+    }
+
+    #[test]
+    fn serde_errors() {
+        assert_eq!(
+            to_bytes(&Some(1u32)).unwrap_err(),
+            SerdeError::Unsupported("options")
+        );
+        assert_eq!(
+            from_bytes::<f64>(&[0u8; 32]).unwrap_err(),
+            SerdeError::Unsupported("floating point numbers")
+        );
+
+        let mut encoded = to_bytes(&transfer()).unwrap();
+        encoded.truncate(64);
+        match from_bytes::<Transfer>(&encoded).unwrap_err() {
+            SerdeError::Decode(err) => {
+                assert_eq!(err.argument, Some(0));
+                assert_eq!(
+                    err.expected,
+                    Some("(uint64,string,bytes,bytes4,uint32[],int32,bool)".to_owned())
+                );
+            }
+            err => panic!("unexpected error: {:?}", err),
+        }
+
+        // uint8 of the enum does not fit the variant index
+        let encoded = encode(3u32);
+        assert!(from_bytes::<Color>(&encoded).is_err());
+    }
+}
+
+mod hash {
+    use super::super::*;
+    use lib::*;
+
+    const TRANSFER: u32 = selector("transfer(address,uint256)");
+    const TRANSFER_TOPIC: [u8; 32] = event_topic("Transfer(address,address,uint256)");
+
+    #[test]
+    fn selector_constant() {
+        assert_eq!(TRANSFER, 0xa9059cbb);
+        assert_eq!(
+            TRANSFER,
+            Signature::parse("transfer(address,uint)").unwrap().selector()
+        );
+    }
+
+    #[test]
+    fn event_topic_constant() {
+        assert_eq!(
+            TRANSFER_TOPIC,
+            hex!("ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef")
+        );
+    }
+
+    fn tiny_keccak(bytes: &[u8]) -> [u8; 32] {
+        let mut keccak = ::tiny_keccak::Keccak::new_keccak256();
+        let mut result = [0u8; 32];
+        keccak.update(bytes);
+        keccak.finalize(&mut result);
+        result
+    }
+
+    #[test]
+    fn const_keccak_matches_tiny_keccak() {
+        // lengths around the 136-byte rate of keccak256 exercise the padding edge cases
+        let input: Vec<u8> = (0..300u32).map(|i| (i * 7) as u8).collect();
+        for len in 0..input.len() {
+            assert_eq!(const_keccak256(&input[..len]), tiny_keccak(&input[..len]), "length {}", len);
+        }
+
+        // pseudo-random inputs of up to three blocks, from a fixed xorshift seed
+        let mut state = 0x2545_f491_4f6c_dd1du64;
+        let mut next = || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+        for _ in 0..200 {
+            let len = (next() % 409) as usize;
+            let input: Vec<u8> = (0..len).map(|_| next() as u8).collect();
+            assert_eq!(const_keccak256(&input), tiny_keccak(&input), "input {:?}", input);
+            assert_eq!(keccak256(&input).0, tiny_keccak(&input));
+        }
+    }
+}
@@ -1,6 +1,8 @@
 //! Utility module
 
+use super::types::H256;
 use lib::*;
+use tiny_keccak::Keccak;
 
 pub type Hash = [u8; 32];
 
@@ -59,3 +61,31 @@ pub fn pad_i32(value: i32) -> Hash {
     padded[31] = value as u8;
     padded
 }
+
+/// Converts big-endian bytes of an unsigned integer to right aligned array of 32 bytes.
+pub fn pad_unsigned(value: &[u8]) -> Hash {
+    let mut padded = [0u8; 32];
+    padded[32 - value.len()..].copy_from_slice(value);
+    padded
+}
+
+/// Converts big-endian bytes of a signed integer to right aligned,
+/// sign extended array of 32 bytes.
+pub fn pad_signed(value: &[u8]) -> Hash {
+    let mut padded = if value[0] & 0x80 != 0 {
+        [0xffu8; 32]
+    } else {
+        [0u8; 32]
+    };
+    padded[32 - value.len()..].copy_from_slice(value);
+    padded
+}
+
+/// Keccak-256 hash of the given bytes.
+pub fn keccak(bytes: &[u8]) -> H256 {
+    let mut keccak = Keccak::new_keccak256();
+    let mut result = H256::zero();
+    keccak.update(bytes);
+    keccak.finalize(result.as_mut());
+    result
+}
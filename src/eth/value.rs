@@ -0,0 +1,325 @@
+//! Runtime-typed values
+
+use super::{types::*, AbiType, DecodeError, Error, Sink, Stream};
+use int::{fits_signed, fits_unsigned, valid_width};
+use lib::*;
+
+/// Solidity type known only at runtime
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParamType {
+    /// Unsigned integer of the given bit width (`uintN`)
+    Uint(usize),
+    /// Signed integer of the given bit width (`intN`)
+    Int(usize),
+    /// Address (`address`)
+    Address,
+    /// Boolean (`bool`)
+    Bool,
+    /// Dynamic byte sequence (`bytes`)
+    Bytes,
+    /// Byte sequence of the given length (`bytesN`)
+    FixedBytes(usize),
+    /// Utf-8 string (`string`)
+    String,
+    /// Dynamic array (`T[]`)
+    Array(Box<ParamType>),
+    /// Array of the given length (`T[N]`)
+    FixedArray(Box<ParamType>, usize),
+    /// Tuple (`(T1,T2,...)`)
+    Tuple(Vec<ParamType>),
+}
+
+impl ParamType {
+    /// Whether type has fixed length or not, see `AbiType::IS_FIXED`
+    pub fn is_fixed(&self) -> bool {
+        match *self {
+            ParamType::Bytes | ParamType::String | ParamType::Array(_) => false,
+            ParamType::FixedArray(ref elem, _) => elem.is_fixed(),
+            ParamType::Tuple(ref members) => members.iter().all(ParamType::is_fixed),
+            _ => true,
+        }
+    }
+
+    /// Number of 32-byte words the type occupies in the head, see `AbiType::HEAD_WORDS`
+    ///
+    /// Saturates at `usize::MAX` for types too large to be encoded, see `validate`.
+    pub fn head_words(&self) -> usize {
+        self.checked_head_words().unwrap_or(usize::MAX)
+    }
+
+    fn checked_head_words(&self) -> Option<usize> {
+        if !self.is_fixed() {
+            return Some(1);
+        }
+        match *self {
+            ParamType::FixedArray(ref elem, len) => len.checked_mul(elem.checked_head_words()?),
+            ParamType::Tuple(ref members) => members.iter().try_fold(0usize, |words, member| {
+                words.checked_add(member.checked_head_words()?)
+            }),
+            _ => Some(1),
+        }
+    }
+
+    /// Check that the type is valid and can be encoded
+    ///
+    /// Fails with `Error::InvalidParamType` for integer widths which are not
+    /// a multiple of 8 between 8 and 256, `bytesN` other than `bytes1` to
+    /// `bytes32`, and fixed arrays and tuples too large to be addressed.
+    pub fn validate(&self) -> Result<(), Error> {
+        let valid = match *self {
+            ParamType::Uint(bits) | ParamType::Int(bits) => valid_width(bits),
+            ParamType::FixedBytes(len) => (1..=32).contains(&len),
+            ParamType::Array(ref elem) => return elem.validate(),
+            ParamType::FixedArray(ref elem, _) => {
+                elem.validate()?;
+                self.fits_head()
+            }
+            ParamType::Tuple(ref members) => {
+                for member in members {
+                    member.validate()?;
+                }
+                self.fits_head()
+            }
+            ParamType::Address | ParamType::Bool | ParamType::Bytes | ParamType::String => true,
+        };
+        if valid {
+            Ok(())
+        } else {
+            Err(Error::InvalidParamType)
+        }
+    }
+
+    /// Whether the head of the type is addressable in bytes
+    fn fits_head(&self) -> bool {
+        self.checked_head_words()
+            .and_then(|words| words.checked_mul(32))
+            .is_some()
+    }
+}
+
+impl fmt::Display for ParamType {
+    /// Canonical name of the type, as used in signatures
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ParamType::Uint(bits) => write!(f, "uint{}", bits),
+            ParamType::Int(bits) => write!(f, "int{}", bits),
+            ParamType::Address => write!(f, "address"),
+            ParamType::Bool => write!(f, "bool"),
+            ParamType::Bytes => write!(f, "bytes"),
+            ParamType::FixedBytes(len) => write!(f, "bytes{}", len),
+            ParamType::String => write!(f, "string"),
+            ParamType::Array(ref elem) => write!(f, "{}[]", elem),
+            ParamType::FixedArray(ref elem, len) => write!(f, "{}[{}]", elem, len),
+            ParamType::Tuple(ref members) => {
+                write!(f, "(")?;
+                for (i, member) in members.iter().enumerate() {
+                    if i != 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", member)?;
+                }
+                write!(f, ")")
+            }
+        }
+    }
+}
+
+/// Value of a Solidity type known only at runtime
+///
+/// Encodes to the same bytes as the corresponding rust type does through `AbiType`.
+#[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub enum AbiValue {
+    /// Unsigned integer of any width
+    Uint(U256),
+    /// Signed integer of any width
+    Int(I256),
+    /// Address
+    Address(Address),
+    /// Boolean
+    Bool(bool),
+    /// Dynamic byte sequence
+    Bytes(Vec<u8>),
+    /// Byte sequence of fixed length
+    FixedBytes(Vec<u8>),
+    /// Utf-8 string
+    String(String),
+    /// Dynamic array
+    Array(Vec<AbiValue>),
+    /// Array of fixed length
+    FixedArray(Vec<AbiValue>),
+    /// Tuple
+    Tuple(Vec<AbiValue>),
+}
+
+impl AbiValue {
+    /// Whether value has fixed length or not, see `AbiType::IS_FIXED`
+    pub fn is_fixed(&self) -> bool {
+        match *self {
+            AbiValue::Bytes(_) | AbiValue::String(_) | AbiValue::Array(_) => false,
+            AbiValue::FixedArray(ref members) | AbiValue::Tuple(ref members) => {
+                members.iter().all(AbiValue::is_fixed)
+            }
+            _ => true,
+        }
+    }
+
+    /// Number of 32-byte words the value occupies in the head, see `AbiType::HEAD_WORDS`
+    pub fn head_words(&self) -> usize {
+        if !self.is_fixed() {
+            return 1;
+        }
+        match *self {
+            AbiValue::FixedBytes(ref bytes) => (bytes.len() + 31) / 32,
+            AbiValue::FixedArray(ref members) | AbiValue::Tuple(ref members) => {
+                members.iter().map(AbiValue::head_words).sum()
+            }
+            _ => 1,
+        }
+    }
+
+    fn decode(stream: &mut Stream, param: &ParamType) -> Result<Self, Error> {
+        match *param {
+            ParamType::Uint(bits) => {
+                if !valid_width(bits) {
+                    return Err(Error::InvalidParamType);
+                }
+                let value = U256::decode(stream)?;
+                if !fits_unsigned(&value, bits) {
+                    return Err(Error::IntegerOverflow);
+                }
+                Ok(AbiValue::Uint(value))
+            }
+            ParamType::Int(bits) => {
+                if !valid_width(bits) {
+                    return Err(Error::InvalidParamType);
+                }
+                let value = I256::decode(stream)?;
+                if !fits_signed(&value, bits) {
+                    return Err(Error::IntegerOverflow);
+                }
+                Ok(AbiValue::Int(value))
+            }
+            ParamType::Address => Ok(AbiValue::Address(Address::decode(stream)?)),
+            ParamType::Bool => Ok(AbiValue::Bool(bool::decode(stream)?)),
+            ParamType::Bytes => Ok(AbiValue::Bytes(Vec::<u8>::decode(stream)?)),
+            ParamType::FixedBytes(len) if (1..=32).contains(&len) => {
+                Ok(AbiValue::FixedBytes(stream.take(len)?.to_vec()))
+            }
+            ParamType::FixedBytes(_) => Err(Error::InvalidParamType),
+            ParamType::String => Ok(AbiValue::String(String::decode(stream)?)),
+            ParamType::Array(ref elem) => {
+                let len = u32::decode(stream)? as usize;
+                Ok(AbiValue::Array(decode_members(stream, elem, len)?))
+            }
+            ParamType::FixedArray(ref elem, len) => {
+                if !param.fits_head() {
+                    return Err(Error::InvalidParamType);
+                }
+                Ok(AbiValue::FixedArray(decode_members(stream, elem, len)?))
+            }
+            ParamType::Tuple(ref members) => {
+                let mut result = Vec::with_capacity(members.len());
+                for member in members {
+                    result.push(stream.pop_value(member)?);
+                }
+                Ok(AbiValue::Tuple(result))
+            }
+        }
+    }
+
+    fn encode(self, sink: &mut Sink) {
+        match self {
+            AbiValue::Uint(value) => value.encode(sink),
+            AbiValue::Int(value) => value.encode(sink),
+            AbiValue::Address(value) => value.encode(sink),
+            AbiValue::Bool(value) => value.encode(sink),
+            AbiValue::Bytes(value) => value.encode(sink),
+            AbiValue::FixedBytes(value) => u8::encode_members(value, sink),
+            AbiValue::String(value) => value.encode(sink),
+            AbiValue::Array(members) => {
+                sink.push(members.len() as u32);
+                encode_members(members, sink);
+            }
+            AbiValue::FixedArray(members) | AbiValue::Tuple(members) => {
+                encode_members(members, sink)
+            }
+        }
+    }
+}
+
+fn encode_members(members: Vec<AbiValue>, sink: &mut Sink) {
+    if members.iter().all(AbiValue::is_fixed) {
+        for member in members {
+            sink.push_value(member);
+        }
+    } else {
+        // offsets of dynamic members are relative to the first member of the array or tuple
+        let mut nested_sink = Sink::new(members.iter().map(AbiValue::head_words).sum());
+        for member in members {
+            nested_sink.push_value(member);
+        }
+        nested_sink.drain_to(sink.preamble_mut());
+    }
+}
+
+fn decode_members(
+    stream: &mut Stream,
+    elem: &ParamType,
+    len: usize,
+) -> Result<Vec<AbiValue>, Error> {
+    stream.check_members(len, elem.head_words())?;
+    stream.pop_members(|stream| {
+        let mut result = Vec::with_capacity(len);
+        for _ in 0..len {
+            result.push(stream.pop_value(elem)?);
+        }
+        Ok(result)
+    })
+}
+
+impl<'a> Stream<'a> {
+    /// Pop next argument of type known at runtime
+    pub fn pop_value(&mut self, param: &ParamType) -> Result<AbiValue, Error> {
+        let start = self.position();
+        let result = if param.is_fixed() {
+            AbiValue::decode(self, param)
+        } else {
+            self.pop_nested(|stream| AbiValue::decode(stream, param))
+        };
+        self.track_error(start, result)
+    }
+}
+
+impl Sink {
+    /// Consume value of type known at runtime to the Sink
+    pub fn push_value(&mut self, value: AbiValue) {
+        if value.is_fixed() {
+            value.encode(self)
+        } else {
+            self.push_nested(|sink| value.encode(sink))
+        }
+    }
+}
+
+/// Decode payload of values of types known at runtime
+pub fn decode_values(params: &[ParamType], payload: &[u8]) -> Result<Vec<AbiValue>, DecodeError> {
+    let mut stream = Stream::new(payload);
+    let mut result = Vec::with_capacity(params.len());
+    for (index, param) in params.iter().enumerate() {
+        match stream.pop_value(param) {
+            Ok(value) => result.push(value),
+            Err(kind) => return Err(stream.error(kind).argument(index, &param.to_string())),
+        }
+    }
+    Ok(result)
+}
+
+/// Encode values of types known at runtime into payload
+pub fn encode_values(values: Vec<AbiValue>) -> Vec<u8> {
+    let mut sink = Sink::new(values.iter().map(AbiValue::head_words).sum());
+    for value in values {
+        sink.push_value(value);
+    }
+    sink.finalize_panicking()
+}
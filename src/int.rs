@@ -0,0 +1,525 @@
+//! Integer types of Solidity which have no native rust counterpart
+
+use lib::*;
+use uint::U256;
+
+/// Error returned when an integer does not fit into the target type
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TryFromIntError;
+
+/// Signed 256-bit integer (Solidity `int256`) in two's complement representation
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct I256(U256);
+
+impl I256 {
+    /// Zero (additive identity) of this type
+    pub fn zero() -> Self {
+        I256(U256::zero())
+    }
+
+    /// One (multiplicative identity) of this type
+    pub fn one() -> Self {
+        I256(U256::one())
+    }
+
+    /// The largest value that can be represented by this type (2^255 - 1)
+    pub fn max_value() -> Self {
+        I256(U256::max_value() >> 1)
+    }
+
+    /// The smallest value that can be represented by this type (-2^255)
+    pub fn min_value() -> Self {
+        I256(U256::one() << 255)
+    }
+
+    /// Instantiate from raw two's complement representation
+    pub fn from_raw(raw: U256) -> Self {
+        I256(raw)
+    }
+
+    /// Raw two's complement representation
+    pub fn into_raw(self) -> U256 {
+        self.0
+    }
+
+    /// Whether the value is zero
+    pub fn is_zero(&self) -> bool {
+        self.0.is_zero()
+    }
+
+    /// Whether the value is negative
+    pub fn is_negative(&self) -> bool {
+        self.0.bit(255)
+    }
+
+    /// Absolute value as an unsigned integer, never overflows
+    pub fn unsigned_abs(self) -> U256 {
+        if self.is_negative() {
+            twos_complement(self.0)
+        } else {
+            self.0
+        }
+    }
+
+    /// Negation, returns a boolean indicating whether an overflow happened
+    pub fn overflowing_neg(self) -> (Self, bool) {
+        (I256(twos_complement(self.0)), self == I256::min_value())
+    }
+
+    /// Addition, returns a boolean indicating whether an overflow happened
+    pub fn overflowing_add(self, other: I256) -> (Self, bool) {
+        let result = I256(self.0.overflowing_add(other.0).0);
+        let overflow = self.is_negative() == other.is_negative()
+            && result.is_negative() != self.is_negative();
+        (result, overflow)
+    }
+
+    /// Subtraction, returns a boolean indicating whether an overflow happened
+    pub fn overflowing_sub(self, other: I256) -> (Self, bool) {
+        let result = I256(self.0.overflowing_sub(other.0).0);
+        let overflow = self.is_negative() != other.is_negative()
+            && result.is_negative() != self.is_negative();
+        (result, overflow)
+    }
+
+    /// Multiplication, returns a boolean indicating whether an overflow happened
+    pub fn overflowing_mul(self, other: I256) -> (Self, bool) {
+        let result = I256(self.0.overflowing_mul(other.0).0);
+        let (abs, abs_overflow) = self.unsigned_abs().overflowing_mul(other.unsigned_abs());
+        let overflow = abs_overflow || if self.is_negative() != other.is_negative() {
+            abs > I256::min_value().0
+        } else {
+            abs > I256::max_value().0
+        };
+        (result, overflow)
+    }
+
+    /// Checked addition, `None` on overflow
+    pub fn checked_add(self, other: I256) -> Option<Self> {
+        checked(self.overflowing_add(other))
+    }
+
+    /// Checked subtraction, `None` on overflow
+    pub fn checked_sub(self, other: I256) -> Option<Self> {
+        checked(self.overflowing_sub(other))
+    }
+
+    /// Checked multiplication, `None` on overflow
+    pub fn checked_mul(self, other: I256) -> Option<Self> {
+        checked(self.overflowing_mul(other))
+    }
+
+    /// Checked division (rounding towards zero), `None` on overflow or division by zero
+    pub fn checked_div(self, other: I256) -> Option<Self> {
+        if other.is_zero() || (self == I256::min_value() && other == -I256::one()) {
+            return None;
+        }
+        let abs = self.unsigned_abs() / other.unsigned_abs();
+        Some(with_sign(abs, self.is_negative() != other.is_negative()))
+    }
+
+    /// Checked remainder (sign follows the dividend), `None` on division by zero
+    pub fn checked_rem(self, other: I256) -> Option<Self> {
+        if other.is_zero() {
+            return None;
+        }
+        let abs = self.unsigned_abs() % other.unsigned_abs();
+        Some(with_sign(abs, self.is_negative()))
+    }
+}
+
+fn twos_complement(value: U256) -> U256 {
+    (!value).overflowing_add(U256::one()).0
+}
+
+fn with_sign(abs: U256, negative: bool) -> I256 {
+    if negative {
+        I256(twos_complement(abs))
+    } else {
+        I256(abs)
+    }
+}
+
+fn checked((result, overflow): (I256, bool)) -> Option<I256> {
+    if overflow {
+        None
+    } else {
+        Some(result)
+    }
+}
+
+/// Widen `u128` to `U256`
+pub fn u256_from_u128(value: u128) -> U256 {
+    (U256::from((value >> 64) as u64) << 64) | U256::from(value as u64)
+}
+
+/// Low 128 bits of `U256`
+#[cfg(feature = "serde")]
+pub fn u256_low_u128(value: &U256) -> u128 {
+    (((*value >> 64).low_u64() as u128) << 64) | value.low_u64() as u128
+}
+
+macro_rules! impl_binop {
+    ($trait: ident, $method: ident, $checked: ident) => {
+        impl ops::$trait for I256 {
+            type Output = I256;
+
+            fn $method(self, other: I256) -> I256 {
+                self.$checked(other).expect("arithmetic operation overflow")
+            }
+        }
+    };
+}
+
+impl_binop!(Add, add, checked_add);
+impl_binop!(Sub, sub, checked_sub);
+impl_binop!(Mul, mul, checked_mul);
+impl_binop!(Div, div, checked_div);
+impl_binop!(Rem, rem, checked_rem);
+
+impl ops::Neg for I256 {
+    type Output = I256;
+
+    fn neg(self) -> I256 {
+        let (result, overflow) = self.overflowing_neg();
+        if overflow {
+            panic!("arithmetic operation overflow");
+        }
+        result
+    }
+}
+
+impl Ord for I256 {
+    fn cmp(&self, other: &I256) -> cmp::Ordering {
+        match (self.is_negative(), other.is_negative()) {
+            (true, false) => cmp::Ordering::Less,
+            (false, true) => cmp::Ordering::Greater,
+            _ => self.0.cmp(&other.0),
+        }
+    }
+}
+
+impl PartialOrd for I256 {
+    fn partial_cmp(&self, other: &I256) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl From<i64> for I256 {
+    fn from(value: i64) -> I256 {
+        I256::from(value as i128)
+    }
+}
+
+impl From<i128> for I256 {
+    fn from(value: i128) -> I256 {
+        if value < 0 {
+            I256(!u256_from_u128(!value as u128))
+        } else {
+            I256(u256_from_u128(value as u128))
+        }
+    }
+}
+
+impl convert::TryFrom<U256> for I256 {
+    type Error = TryFromIntError;
+
+    fn try_from(value: U256) -> Result<I256, TryFromIntError> {
+        if value.bit(255) {
+            return Err(TryFromIntError);
+        }
+        Ok(I256(value))
+    }
+}
+
+impl convert::TryFrom<I256> for U256 {
+    type Error = TryFromIntError;
+
+    fn try_from(value: I256) -> Result<U256, TryFromIntError> {
+        if value.is_negative() {
+            return Err(TryFromIntError);
+        }
+        Ok(value.0)
+    }
+}
+
+impl convert::TryFrom<I256> for i128 {
+    type Error = TryFromIntError;
+
+    fn try_from(value: I256) -> Result<i128, TryFromIntError> {
+        // all bits above the 128 low ones must be the sign extension
+        let sign_bits = if value.is_negative() { !value.0 } else { value.0 };
+        if !(sign_bits >> 127).is_zero() {
+            return Err(TryFromIntError);
+        }
+        let low = value.0.low_u64() as u128;
+        let high = (value.0 >> 64).low_u64() as u128;
+        Ok(((high << 64) | low) as i128)
+    }
+}
+
+impl convert::TryFrom<I256> for i64 {
+    type Error = TryFromIntError;
+
+    fn try_from(value: I256) -> Result<i64, TryFromIntError> {
+        let value: i128 = convert::TryFrom::try_from(value)?;
+        if value < i64::min_value() as i128 || value > i64::max_value() as i128 {
+            return Err(TryFromIntError);
+        }
+        Ok(value as i64)
+    }
+}
+
+/// Whether the value fits into `bits` bits
+pub fn fits_unsigned(value: &U256, bits: usize) -> bool {
+    value.bits() <= bits
+}
+
+/// Whether the value fits into `bits` bits of two's complement representation
+pub fn fits_signed(value: &I256, bits: usize) -> bool {
+    // all bits above the `bits - 1` low ones must be the sign extension
+    let sign_bits = if value.is_negative() { !value.0 } else { value.0 };
+    (sign_bits >> (bits - 1)).is_zero()
+}
+
+/// Whether `bits` is a valid integer width, a multiple of 8 between 8 and 256
+pub fn valid_width(bits: usize) -> bool {
+    bits % 8 == 0 && bits >= 8 && bits <= 256
+}
+
+fn assert_valid_width(bits: usize) {
+    assert!(
+        valid_width(bits),
+        "integer width must be a multiple of 8 between 8 and 256"
+    );
+}
+
+/// Unsigned integer of `BITS` width (Solidity `uint<BITS>`)
+///
+/// `BITS` must be a multiple of 8 between 8 and 256, the value is
+/// guaranteed to fit into `BITS` bits.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Uint<const BITS: usize>(U256);
+
+impl<const BITS: usize> Uint<BITS> {
+    /// Instantiate from value, fails if it does not fit into `BITS` bits
+    /// or `BITS` is not a valid width
+    pub fn new(value: U256) -> Result<Self, TryFromIntError> {
+        if !valid_width(BITS) || !fits_unsigned(&value, BITS) {
+            return Err(TryFromIntError);
+        }
+        Ok(Uint(value))
+    }
+
+    /// The largest value that can be represented by this type (2^BITS - 1)
+    ///
+    /// Panics if `BITS` is not a valid width.
+    pub fn max_value() -> Self {
+        assert_valid_width(BITS);
+        Uint(U256::max_value() >> (256 - BITS))
+    }
+
+    /// Underlying value
+    pub fn value(self) -> U256 {
+        self.0
+    }
+}
+
+impl<const BITS: usize> From<Uint<BITS>> for U256 {
+    fn from(value: Uint<BITS>) -> U256 {
+        value.0
+    }
+}
+
+impl<const BITS: usize> convert::TryFrom<U256> for Uint<BITS> {
+    type Error = TryFromIntError;
+
+    fn try_from(value: U256) -> Result<Uint<BITS>, TryFromIntError> {
+        Uint::new(value)
+    }
+}
+
+/// Signed integer of `BITS` width (Solidity `int<BITS>`)
+///
+/// `BITS` must be a multiple of 8 between 8 and 256, the value is
+/// guaranteed to fit into `BITS` bits of two's complement representation.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Int<const BITS: usize>(I256);
+
+impl<const BITS: usize> Int<BITS> {
+    /// Instantiate from value, fails if it does not fit into `BITS` bits
+    /// or `BITS` is not a valid width
+    pub fn new(value: I256) -> Result<Self, TryFromIntError> {
+        if !valid_width(BITS) || !fits_signed(&value, BITS) {
+            return Err(TryFromIntError);
+        }
+        Ok(Int(value))
+    }
+
+    /// The largest value that can be represented by this type (2^(BITS-1) - 1)
+    ///
+    /// Panics if `BITS` is not a valid width.
+    pub fn max_value() -> Self {
+        assert_valid_width(BITS);
+        Int(I256(U256::max_value() >> (257 - BITS)))
+    }
+
+    /// The smallest value that can be represented by this type (-2^(BITS-1))
+    ///
+    /// Panics if `BITS` is not a valid width.
+    pub fn min_value() -> Self {
+        assert_valid_width(BITS);
+        Int(I256(!(U256::max_value() >> (257 - BITS))))
+    }
+
+    /// Underlying value
+    pub fn value(self) -> I256 {
+        self.0
+    }
+}
+
+impl<const BITS: usize> From<Int<BITS>> for I256 {
+    fn from(value: Int<BITS>) -> I256 {
+        value.0
+    }
+}
+
+impl<const BITS: usize> convert::TryFrom<I256> for Int<BITS> {
+    type Error = TryFromIntError;
+
+    fn try_from(value: I256) -> Result<Int<BITS>, TryFromIntError> {
+        Int::new(value)
+    }
+}
+
+#[cfg(feature = "std")]
+impl fmt::Display for I256 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_negative() {
+            write!(f, "-")?;
+        }
+        fmt::Display::fmt(&self.unsigned_abs(), f)
+    }
+}
+
+#[cfg(feature = "std")]
+impl fmt::Debug for I256 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<const BITS: usize> fmt::Display for Uint<BITS> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<const BITS: usize> fmt::Debug for Uint<BITS> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<const BITS: usize> fmt::Display for Int<BITS> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<const BITS: usize> fmt::Debug for Int<BITS> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lib::convert::TryFrom;
+
+    #[test]
+    fn conversions() {
+        assert_eq!(i64::try_from(I256::from(-1i64)), Ok(-1));
+        assert_eq!(i128::try_from(I256::from(i128::min_value())), Ok(i128::min_value()));
+        assert_eq!(i128::try_from(I256::from(i128::max_value())), Ok(i128::max_value()));
+        assert_eq!(
+            i64::try_from(I256::from(i64::min_value() as i128 - 1)),
+            Err(TryFromIntError)
+        );
+        assert_eq!(I256::from(-1i64).into_raw(), U256::max_value());
+        assert_eq!(U256::try_from(I256::from(-1i64)), Err(TryFromIntError));
+        assert_eq!(I256::try_from(U256::max_value()), Err(TryFromIntError));
+        assert_eq!(I256::try_from(U256::from(5)), Ok(I256::from(5i64)));
+    }
+
+    #[test]
+    fn arithmetic() {
+        let a = I256::from(-7i64);
+        let b = I256::from(2i64);
+        assert_eq!(a + b, I256::from(-5i64));
+        assert_eq!(a - b, I256::from(-9i64));
+        assert_eq!(a * b, I256::from(-14i64));
+        assert_eq!(a / b, I256::from(-3i64));
+        assert_eq!(a % b, I256::from(-1i64));
+        assert_eq!(-a, I256::from(7i64));
+        assert!(a < b);
+        assert!(I256::min_value() < a);
+    }
+
+    #[test]
+    fn overflow() {
+        assert_eq!(I256::max_value().checked_add(I256::one()), None);
+        assert_eq!(I256::min_value().checked_sub(I256::one()), None);
+        assert_eq!(I256::min_value().checked_mul(-I256::one()), None);
+        assert_eq!(I256::min_value().checked_div(-I256::one()), None);
+        assert_eq!(
+            I256::min_value().checked_mul(I256::one()),
+            Some(I256::min_value())
+        );
+        assert_eq!(I256::min_value().overflowing_neg(), (I256::min_value(), true));
+        assert_eq!(I256::min_value().unsigned_abs(), U256::one() << 255);
+    }
+
+    #[test]
+    fn bounded_unsigned() {
+        assert_eq!(Uint::<24>::max_value().value(), U256::from(0xff_ffff));
+        assert_eq!(Uint::<24>::new(U256::from(0xff_ffff)), Ok(Uint::<24>::max_value()));
+        assert_eq!(Uint::<24>::new(U256::from(0x100_0000)), Err(TryFromIntError));
+        assert_eq!(Uint::<256>::max_value().value(), U256::max_value());
+        assert_eq!(Uint::<8>::try_from(U256::from(255)).map(U256::from), Ok(U256::from(255)));
+    }
+
+    #[test]
+    fn bounded_signed() {
+        assert_eq!(Int::<72>::max_value().value(), I256::from((1i128 << 71) - 1));
+        assert_eq!(Int::<72>::min_value().value(), I256::from(-(1i128 << 71)));
+        assert!(Int::<72>::new(I256::from(1i128 << 71)).is_err());
+        assert!(Int::<72>::new(I256::from(-(1i128 << 71) - 1)).is_err());
+        assert_eq!(Int::<8>::new(I256::from(-128i64)), Ok(Int::<8>::min_value()));
+        assert_eq!(Int::<256>::min_value().value(), I256::min_value());
+        assert_eq!(Int::<256>::max_value().value(), I256::max_value());
+    }
+
+    #[test]
+    fn invalid_widths() {
+        assert!(!valid_width(0));
+        assert!(!valid_width(12));
+        assert!(!valid_width(264));
+        assert_eq!(Uint::<0>::new(U256::zero()), Err(TryFromIntError));
+        assert_eq!(Uint::<12>::new(U256::one()), Err(TryFromIntError));
+        assert_eq!(Uint::<264>::new(U256::one()), Err(TryFromIntError));
+        assert_eq!(Int::<0>::new(I256::zero()), Err(TryFromIntError));
+        assert_eq!(Int::<264>::new(I256::one()), Err(TryFromIntError));
+    }
+
+    #[test]
+    #[should_panic(expected = "integer width must be a multiple of 8 between 8 and 256")]
+    fn invalid_width_max_value() {
+        Uint::<12>::max_value();
+    }
+}
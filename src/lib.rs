@@ -7,21 +7,30 @@
 
 extern crate byteorder;
 extern crate owasm_std;
+#[cfg(feature = "serde")]
+extern crate serde;
+extern crate tiny_keccak;
 extern crate uint;
 
 #[cfg(test)]
 #[macro_use]
 extern crate hex_literal;
 
+#[cfg(all(test, feature = "serde"))]
+#[macro_use]
+extern crate serde_derive;
+
 #[cfg(not(feature = "std"))]
 #[allow(unused)]
 #[macro_use]
 extern crate alloc;
 
 pub mod eth;
+mod int;
 
 /// Custom types which AbiType supports
 pub mod types {
+    pub use int::{Int, TryFromIntError, Uint, I256};
     pub use owasm_std::{hash::*, Vec};
     pub use uint::U256;
 }
@@ -58,7 +67,7 @@ mod lib {
     #[cfg(not(feature = "std"))]
     pub use alloc::string::{String, ToString};
     #[cfg(feature = "std")]
-    pub use std::string::String;
+    pub use std::string::{String, ToString};
 
     #[cfg(not(feature = "std"))]
     pub use alloc::vec::Vec;
@@ -37,3 +37,92 @@ fn bytes16() {
 	assert_eq!(endpoint.inner.v1, [0x12, 0x24, 0x36, 0x48, 0x60, 0x72, 0x84, 0x96]);
 	assert_eq!(endpoint.inner.v2, [0x07, 0x14, 0x21, 0x28, 0x35, 0x42, 0x49, 0x56]);
 }
+
+
+#[eth_abi(FixedArrayEndpoint, FixedArrayClient)]
+pub trait FixedArrayContract {
+	fn sum(&mut self, v: [[u64; 2]; 2]) -> u64;
+	fn words(&mut self, v: [Vec<u8>; 2]) -> [u32; 2];
+	fn count(&mut self, v: [u8; 40]) -> u32;
+}
+
+// sum([[1, 2], [3, 4]])
+const PAYLOAD_SUM: &[u8] = &[
+	0xb8, 0xa4, 0x7e, 0x67,
+	0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01,
+	0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02,
+	0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03,
+	0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x04,
+];
+
+// words([[1, 2], [3]])
+const PAYLOAD_WORDS: &[u8] = &[
+	0x6b, 0xf8, 0x6f, 0x47,
+	0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x20,
+	0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x40,
+	0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x80,
+	0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02,
+	0x01, 0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+	0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01,
+	0x03, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+];
+
+#[test]
+fn fixed_arrays() {
+	pub struct Instance;
+
+	impl FixedArrayContract for Instance {
+		fn sum(&mut self, v: [[u64; 2]; 2]) -> u64 {
+			v.iter().flat_map(|inner| inner.iter()).sum()
+		}
+		fn words(&mut self, v: [Vec<u8>; 2]) -> [u32; 2] {
+			[v[0].len() as u32, v[1].len() as u32]
+		}
+		fn count(&mut self, v: [u8; 40]) -> u32 {
+			v.iter().map(|x| *x as u32).sum()
+		}
+	}
+
+	let mut endpoint = FixedArrayEndpoint::new(Instance);
+
+	let res_sum = endpoint.dispatch(PAYLOAD_SUM);
+	assert_eq!(&res_sum[..], &[
+		0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0a,
+	][..]);
+
+	let res_words = endpoint.dispatch(PAYLOAD_WORDS);
+	assert_eq!(&res_words[..], &[
+		0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02,
+		0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01,
+	][..]);
+}
+
+#[test]
+fn long_byte_array() {
+	pub struct Instance;
+
+	impl FixedArrayContract for Instance {
+		fn sum(&mut self, _v: [[u64; 2]; 2]) -> u64 {
+			0
+		}
+		fn words(&mut self, _v: [Vec<u8>; 2]) -> [u32; 2] {
+			[0, 0]
+		}
+		fn count(&mut self, v: [u8; 40]) -> u32 {
+			v.iter().map(|x| *x as u32).sum()
+		}
+	}
+
+	// count([1; 40]), `[u8; 40]` is `uint8[40]` with one word per member
+	let mut payload = vec![0x1a, 0x92, 0x9c, 0x9c];
+	for _ in 0..40 {
+		payload.extend_from_slice(&[0u8; 31]);
+		payload.push(1);
+	}
+
+	let mut endpoint = FixedArrayEndpoint::new(Instance);
+	let result = endpoint.dispatch(&payload);
+	assert_eq!(&result[..], &[
+		0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x28,
+	][..]);
+}
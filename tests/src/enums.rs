@@ -0,0 +1,127 @@
+#![allow(dead_code)]
+
+use pwasm_test::{ext_get, ext_reset};
+use owasm_abi::eth::{encode_packed, AsLog, EndpointInterface};
+use owasm_abi::types::*;
+use owasm_abi_derive::{eth_abi, AbiType};
+
+#[derive(AbiType, Clone, Copy, Debug, PartialEq)]
+pub enum Status {
+	Pending,
+	Settled,
+	Refunded,
+}
+
+#[eth_abi(EnumEndpoint, EnumClient)]
+pub trait EnumContract {
+	fn settle(&mut self, status: Status, history: Vec<Status>) -> Status;
+
+	#[event]
+	fn settled(&mut self, indexed_status: Status, previous: Status);
+}
+
+// settle(Pending, [Settled, Refunded])
+const PAYLOAD_SAMPLE_1: &[u8] = &[
+	0x87, 0x44, 0x9f, 0x04,
+	0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+	0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x40,
+	0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02,
+	0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01,
+	0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02,
+];
+
+// settle(3, []), status is out of range
+const PAYLOAD_SAMPLE_2: &[u8] = &[
+	0x87, 0x44, 0x9f, 0x04,
+	0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03,
+	0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x40,
+	0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+];
+
+pub struct Instance;
+
+impl EnumContract for Instance {
+	fn settle(&mut self, status: Status, history: Vec<Status>) -> Status {
+		assert_eq!(status, Status::Pending);
+		assert_eq!(history, vec![Status::Settled, Status::Refunded]);
+		self.settled(Status::Settled, status);
+		Status::Settled
+	}
+}
+
+#[test]
+fn settle() {
+	ext_reset(|e| e);
+	let mut endpoint = EnumEndpoint::new(Instance);
+	let result = endpoint.dispatch(PAYLOAD_SAMPLE_1);
+
+	assert_eq!(&result[..], &[
+		0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01,
+	][..]);
+
+	let ext = ext_get();
+	let logs = ext.logs();
+	assert_eq!(logs.len(), 1);
+	assert_eq!(logs[0].topics[1], U256::from(1).as_log());
+	assert_eq!(&logs[0].data[..], &[
+		0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+	][..]);
+}
+
+#[test]
+#[should_panic(expected = "argument decoding failed: invalid enum variant at offset 0 in argument 0 of type uint8")]
+fn settle_out_of_range() {
+	let mut endpoint = EnumEndpoint::new(Instance);
+	endpoint.dispatch(PAYLOAD_SAMPLE_2);
+}
+
+#[test]
+fn packed() {
+	assert_eq!(encode_packed((Status::Refunded, Status::Pending)), vec![2u8, 0]);
+	assert_eq!(encode_packed(vec![Status::Refunded])[31], 2);
+}
+
+#[test]
+fn json_abi_enums() {
+	let abi = owasm_abi::eth::json_abi(EnumEndpoint::<Instance>::JSON_ABI);
+
+	assert_eq!(abi, r#"[
+  {
+    "type": "function",
+    "name": "settle",
+    "inputs": [
+      {
+        "name": "status",
+        "type": "uint8"
+      },
+      {
+        "name": "history",
+        "type": "uint8[]"
+      }
+    ],
+    "outputs": [
+      {
+        "name": "returnValue0",
+        "type": "uint8"
+      }
+    ],
+    "constant": false
+  },
+  {
+    "type": "event",
+    "name": "settled",
+    "inputs": [
+      {
+        "name": "indexed_status",
+        "type": "uint8",
+        "indexed": true
+      },
+      {
+        "name": "previous",
+        "type": "uint8",
+        "indexed": false
+      }
+    ]
+  }
+]"#);
+}
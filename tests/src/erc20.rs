@@ -13,6 +13,7 @@ mod contract {
 
 		#[constant] fn balanceOf(&mut self, _owner: Address) -> U256;
 		#[constant] fn totalSupply(&mut self) -> U256;
+		#[constant] fn decimals(&mut self) -> u8;
 
 		fn transfer(&mut self, _to: Address, _amount: U256) -> bool;
 
@@ -41,6 +42,10 @@ mod contract {
 		fn totalSupply(&mut self) -> U256 {
 			self.total_supply
 		}
+
+		fn decimals(&mut self) -> u8 {
+			18
+		}
 	}
 }
 
@@ -63,6 +68,15 @@ fn call1() {
 	endpoint.dispatch(SAMPLE1);
 }
 
+#[test]
+fn decimals() {
+	let mut endpoint = contract::Endpoint::new(contract::Instance::default());
+	let result = endpoint.dispatch(&[0x31, 0x3c, 0xe5, 0x67]);
+
+	assert_eq!(&result[..31], &[0u8; 31][..]);
+	assert_eq!(result[31], 18);
+}
+
 #[test]
 fn ctor() {
 	let mut endpoint = contract::Endpoint::new(contract::Instance::default());
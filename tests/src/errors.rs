@@ -0,0 +1,193 @@
+#![allow(dead_code)]
+
+use owasm_abi::eth::{ContractError, EndpointInterface, Error};
+use owasm_abi::types::*;
+use owasm_abi_derive::eth_abi;
+
+use pwasm_test::{ext_reset, Endpoint};
+
+#[eth_abi(BankEndpoint, BankClient)]
+pub trait Bank {
+	fn withdraw(&mut self, amount: U256) -> Result<U256, BankError>;
+	fn close(&mut self) -> Result<(), Closed>;
+
+	#[error]
+	fn InsufficientBalance(&mut self, needed: U256, available: U256);
+	#[error]
+	fn Closed(&mut self, reason: &str);
+}
+
+// withdraw(5)
+const PAYLOAD_SAMPLE_1: &[u8] = &[
+	0x2e, 0x1a, 0x7d, 0x4d,
+	0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x05,
+];
+
+// withdraw(20)
+const PAYLOAD_SAMPLE_2: &[u8] = &[
+	0x2e, 0x1a, 0x7d, 0x4d,
+	0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x14,
+];
+
+// close()
+const PAYLOAD_SAMPLE_3: &[u8] = &[0x43, 0xd7, 0x26, 0xd6];
+
+// InsufficientBalance(20, 10)
+const ERROR_SAMPLE_1: &[u8] = &[
+	0xcf, 0x47, 0x91, 0x81,
+	0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x14,
+	0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0a,
+];
+
+// Closed("frozen")
+const ERROR_SAMPLE_2: &[u8] = &[
+	0xde, 0x25, 0x98, 0xb8,
+	0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x20,
+	0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x06,
+	b'f', b'r', b'o', b'z', b'e', b'n', 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+];
+
+pub struct Instance {
+	balance: U256,
+}
+
+impl Bank for Instance {
+	fn withdraw(&mut self, amount: U256) -> Result<U256, BankError> {
+		if amount > self.balance {
+			return Err(InsufficientBalance { needed: amount, available: self.balance }.into());
+		}
+		self.balance = self.balance - amount;
+		Ok(self.balance)
+	}
+
+	fn close(&mut self) -> Result<(), Closed> {
+		Err(Closed { reason: "frozen".to_owned() })
+	}
+}
+
+#[test]
+fn selectors() {
+	assert_eq!(InsufficientBalance::SELECTOR, 0xcf479181);
+	assert_eq!(Closed::SELECTOR, 0xde2598b8);
+}
+
+#[test]
+fn withdraw() {
+	let mut endpoint = BankEndpoint::new(Instance { balance: U256::from(10) });
+	let result = endpoint.dispatch(PAYLOAD_SAMPLE_1);
+
+	assert_eq!(&result[..], &[
+		0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x05,
+	][..]);
+}
+
+#[test]
+fn withdraw_insufficient() {
+	let mut endpoint = BankEndpoint::new(Instance { balance: U256::from(10) });
+	let revert = endpoint.try_dispatch(PAYLOAD_SAMPLE_2).err().expect("withdraw should revert");
+	assert_eq!(revert.payload(), ERROR_SAMPLE_1);
+
+	match BankError::decode(revert.payload()) {
+		Ok(BankError::InsufficientBalance(error)) => {
+			assert_eq!(error.needed, U256::from(20));
+			assert_eq!(error.available, U256::from(10));
+		},
+		_ => panic!("expected InsufficientBalance"),
+	}
+}
+
+#[test]
+fn close_reverts() {
+	let mut endpoint = BankEndpoint::new(Instance { balance: U256::from(10) });
+	let revert = endpoint.try_dispatch(PAYLOAD_SAMPLE_3).err().expect("close should revert");
+	assert_eq!(revert.payload(), ERROR_SAMPLE_2);
+
+	let error = Closed::decode(revert.payload()).expect("revert payload should decode");
+	assert_eq!(error.reason, "frozen");
+}
+
+#[test]
+#[should_panic(expected = "revert payload 0xcf479181")]
+fn withdraw_insufficient_dispatch() {
+	let mut endpoint = BankEndpoint::new(Instance { balance: U256::from(10) });
+	endpoint.dispatch(PAYLOAD_SAMPLE_2);
+}
+
+// stands for `owasm_ethereum::ret`, which the `call` export of a contract returns with
+fn ret(result: &[u8]) -> ! {
+	panic!("ret {:?}", result)
+}
+
+#[test]
+#[should_panic(expected = "ret [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 5]")]
+fn withdraw_handle_call() {
+	let mut endpoint = BankEndpoint::new(Instance { balance: U256::from(10) });
+	endpoint.handle_call(PAYLOAD_SAMPLE_1, ret);
+}
+
+#[test]
+#[should_panic(expected = "revert payload 0xcf4791810000000000000000000000000000000000000000000000000000000000000014000000000000000000000000000000000000000000000000000000000000000a")]
+fn withdraw_insufficient_handle_call() {
+	let mut endpoint = BankEndpoint::new(Instance { balance: U256::from(10) });
+	endpoint.handle_call(PAYLOAD_SAMPLE_2, ret);
+}
+
+#[test]
+fn bank_call() {
+	ext_reset(|e| e.endpoint(Address::zero(), Endpoint::new(Box::new(|_, input, result| {
+		let mut endpoint = BankEndpoint::new(Instance { balance: U256::from(10) });
+		match endpoint.try_dispatch(input) {
+			Ok(output) => {
+				result[..output.len()].copy_from_slice(&output);
+				0
+			},
+			Err(revert) => {
+				result[..revert.payload().len()].copy_from_slice(revert.payload());
+				1
+			},
+		}
+	}))));
+	let mut client = BankClient::new(Address::zero());
+
+	assert_eq!(client.withdraw(U256::from(5)).ok(), Some(U256::from(5)));
+	match client.withdraw(U256::from(20)) {
+		Err(BankError::InsufficientBalance(error)) => {
+			assert_eq!(error.needed, U256::from(20));
+			assert_eq!(error.available, U256::from(10));
+		},
+		_ => panic!("expected InsufficientBalance"),
+	}
+	match client.close() {
+		Err(error) => assert_eq!(error.reason, "frozen"),
+		Ok(()) => panic!("expected Closed"),
+	}
+}
+
+#[test]
+fn error_payloads() {
+	let mut instance = Instance { balance: U256::from(10) };
+	let error = instance.withdraw(U256::from(20)).err().expect("withdraw should fail");
+	assert_eq!(&error.encode()[..], ERROR_SAMPLE_1);
+
+	let error = instance.close().err().expect("close should fail");
+	assert_eq!(&error.encode()[..], ERROR_SAMPLE_2);
+}
+
+#[test]
+fn errors_decoded() {
+	match BankError::decode(ERROR_SAMPLE_1) {
+		Ok(BankError::InsufficientBalance(error)) => {
+			assert_eq!(error.needed, U256::from(20));
+			assert_eq!(error.available, U256::from(10));
+		},
+		_ => panic!("expected InsufficientBalance"),
+	}
+
+	match BankError::decode(ERROR_SAMPLE_2) {
+		Ok(BankError::Closed(error)) => assert_eq!(error.reason, "frozen"),
+		_ => panic!("expected Closed"),
+	}
+
+	assert_eq!(Closed::decode(ERROR_SAMPLE_1).err(), Some(Error::UnknownSelector));
+	assert_eq!(BankError::decode(&[0x08, 0xc3, 0x79, 0xa0]).err(), Some(Error::UnknownSelector));
+}
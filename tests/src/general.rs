@@ -113,6 +113,20 @@ fn sam_dispatch() {
 	assert!(!endpoint.inner.called_wrong, "wrong method was invoked");
 }
 
+#[test]
+fn sam_payload_annotated() {
+	use owasm_abi::eth::{annotate, Signature};
+
+	let signature = Signature::parse("sam(bytes,bool,uint256[])").unwrap();
+	let dump = annotate(PAYLOAD_SAMPLE_2, &signature.inputs);
+
+	assert!(dump.starts_with(&format!("selector  {:08x}\n", signature.selector())));
+	assert!(dump.contains("offset  arg0 (bytes) -> 0x0060"));
+	assert!(dump.contains("data    arg0 (bytes): 4 bytes + padding, non-zero padding"));
+	assert!(dump.contains("head    arg2[2] (uint256) = 3"));
+	assert!(!dump.contains("??"), "unexpected words in:\n{}", dump);
+}
+
 #[test]
 fn boo_dispatch() {
 	#[derive(Default)]
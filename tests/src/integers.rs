@@ -0,0 +1,109 @@
+#![allow(dead_code)]
+
+use owasm_abi::eth::{EndpointInterface, Revert, RevertReason};
+use owasm_abi::types::*;
+use owasm_abi_derive::eth_abi;
+
+#[eth_abi(IntegerEndpoint, IntegerClient)]
+pub trait IntegerContract {
+	fn scale(&mut self, factor: Uint<24>, value: Int<72>) -> Int<72>;
+	fn add(&mut self, a: U256, b: U256) -> Result<U256, RevertReason>;
+}
+
+// scale(3, -2)
+const PAYLOAD_SAMPLE_1: &[u8] = &[
+	0x75, 0x5d, 0x99, 0xd2,
+	0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03,
+	0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xfe,
+];
+
+// scale(2^24, -2), factor does not fit into uint24
+const PAYLOAD_SAMPLE_2: &[u8] = &[
+	0x75, 0x5d, 0x99, 0xd2,
+	0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00,
+	0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xfe,
+];
+
+// add(2^256 - 1, 1)
+const PAYLOAD_SAMPLE_3: &[u8] = &[
+	0x77, 0x16, 0x02, 0xf7,
+	0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+	0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01,
+];
+
+// Panic(0x11), arithmetic overflow
+const REVERT_SAMPLE_1: &[u8] = &[
+	0x4e, 0x48, 0x7b, 0x71,
+	0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x11,
+];
+
+pub struct Instance;
+
+impl IntegerContract for Instance {
+	fn scale(&mut self, factor: Uint<24>, value: Int<72>) -> Int<72> {
+		let factor = I256::from(factor.value().low_u64() as i64);
+		Int::new(value.value() * factor).expect("int72 overflow")
+	}
+
+	fn add(&mut self, a: U256, b: U256) -> Result<U256, RevertReason> {
+		match a.overflowing_add(b) {
+			(_, true) => Err(RevertReason::Panic(U256::from(0x11))),
+			(sum, false) => Ok(sum),
+		}
+	}
+}
+
+#[test]
+fn scale() {
+	let mut endpoint = IntegerEndpoint::new(Instance);
+	let result = endpoint.dispatch(PAYLOAD_SAMPLE_1);
+
+	assert_eq!(&result[..], &[
+		0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xfa
+	][..]);
+}
+
+#[test]
+#[should_panic(expected = "argument decoding failed: integer does not fit into its bit width at offset 0 in argument 0 of type uint24")]
+fn scale_out_of_range() {
+	let mut endpoint = IntegerEndpoint::new(Instance);
+	endpoint.dispatch(PAYLOAD_SAMPLE_2);
+}
+
+#[test]
+fn scale_out_of_range_revert_payload() {
+	let mut endpoint = IntegerEndpoint::new(Instance);
+	let revert = endpoint.try_dispatch(PAYLOAD_SAMPLE_2).err().expect("scale should revert");
+
+	assert_eq!(&revert.payload()[..4], &[0x08, 0xc3, 0x79, 0xa0][..]);
+	assert_eq!(
+		revert.reason(),
+		Ok(RevertReason::Error("argument decoding failed: integer does not fit into its bit width at offset 0 in argument 0 of type uint24".to_owned()))
+	);
+}
+
+// stands for `owasm_ethereum::ret`, which the `call` export of a contract returns with
+fn ret(_result: &[u8]) -> ! {
+	panic!("call should revert")
+}
+
+#[test]
+#[should_panic(expected = "argument decoding failed: integer does not fit into its bit width at offset 0 in argument 0 of type uint24")]
+fn scale_out_of_range_handle_call() {
+	let mut endpoint = IntegerEndpoint::new(Instance);
+	endpoint.handle_call(PAYLOAD_SAMPLE_2, ret);
+}
+
+#[test]
+#[should_panic(expected = "panic code 0x11")]
+fn add_overflow_handle_call() {
+	let mut endpoint = IntegerEndpoint::new(Instance);
+	endpoint.handle_call(PAYLOAD_SAMPLE_3, ret);
+}
+
+#[test]
+fn add_overflow_revert_payload() {
+	let mut endpoint = IntegerEndpoint::new(Instance);
+
+	assert_eq!(endpoint.try_dispatch(PAYLOAD_SAMPLE_3), Err(Revert::new(REVERT_SAMPLE_1.to_vec())));
+}
@@ -17,3 +17,8 @@ mod trivia;
 mod payable;
 mod multiple_return;
 mod general;
+mod strings;
+mod integers;
+mod structs;
+mod enums;
+mod errors;
@@ -1,31 +1,48 @@
 #![allow(dead_code)]
 
 use owasm_abi::eth::EndpointInterface;
+use owasm_abi::types::*;
 use owasm_abi_derive::eth_abi;
 
+use pwasm_test::{ext_reset, Endpoint};
+
 #[eth_abi(TupleReturnEndpoint, TupleReturnClient)]
 pub trait TupleReturnContract {
 	fn ret2(&mut self) -> (u64, u64);
 	fn ret6(&mut self) -> (u64, u64, u64, u64, u64, u64);
 	fn ret_var(&mut self) -> (u64, Vec<u8>);
+	fn sum_len(&mut self, v: (u64, Vec<u8>)) -> u64;
 }
 
-#[test]
-fn multiple_return() {
-	pub struct Instance;
-
-	impl TupleReturnContract for Instance {
-		fn ret2(&mut self) -> (u64, u64) {
-			(2, 2)
-		}
-		fn ret6(&mut self) -> (u64, u64, u64, u64, u64, u64) {
-			(6, 6, 6, 6, 6, 6)
-		}
-		fn ret_var(&mut self) -> (u64, Vec<u8>) {
-			(6, vec![1, 2, 3, 5, 7, 11])
-		}
+// sum_len((6, [1, 2, 3, 5, 7, 11]))
+const PAYLOAD_SUM_LEN: &[u8] = &[
+	0x14, 0xd7, 0xea, 0x42,
+	0u8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 32,
+	0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 6,
+	0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 64,
+	0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 6,
+	1, 2, 3, 5, 7, 11, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0
+];
+
+pub struct Instance;
+
+impl TupleReturnContract for Instance {
+	fn ret2(&mut self) -> (u64, u64) {
+		(2, 2)
 	}
+	fn ret6(&mut self) -> (u64, u64, u64, u64, u64, u64) {
+		(6, 6, 6, 6, 6, 6)
+	}
+	fn ret_var(&mut self) -> (u64, Vec<u8>) {
+		(6, vec![1, 2, 3, 5, 7, 11])
+	}
+	fn sum_len(&mut self, v: (u64, Vec<u8>)) -> u64 {
+		v.0 + v.1.len() as u64
+	}
+}
 
+#[test]
+fn multiple_return() {
 	let mut endpoint = TupleReturnEndpoint::new(Instance);
 
 	let res2 = endpoint.dispatch(&[0xa6, 0x37, 0xe6, 0x9c]);
@@ -51,4 +68,23 @@ fn multiple_return() {
 		0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 6,
 		1, 2, 3, 5, 7, 11, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0
 	][..]);
+
+	let res_sum_len = endpoint.dispatch(PAYLOAD_SUM_LEN);
+	assert_eq!(&res_sum_len[..], &[
+		0u8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 12
+	][..]);
+}
+
+#[test]
+fn multiple_return_call() {
+	ext_reset(|e| e.endpoint(Address::zero(), Endpoint::new(Box::new(|_, input, result| {
+		let output = TupleReturnEndpoint::new(Instance).dispatch(input);
+		result[..output.len()].copy_from_slice(&output);
+		0
+	}))));
+	let mut client = TupleReturnClient::new(Address::zero());
+
+	assert_eq!(client.ret2(), (2, 2));
+	assert_eq!(client.ret_var(), (6, vec![1, 2, 3, 5, 7, 11]));
+	assert_eq!(client.sum_len((6, vec![1, 2, 3, 5, 7, 11])), 12);
 }
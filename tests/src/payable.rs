@@ -1,7 +1,7 @@
 #![allow(dead_code)]
 
 use owasm_abi_derive::eth_abi;
-use owasm_abi::eth::EndpointInterface;
+use owasm_abi::eth::{EndpointInterface, Revert};
 
 use pwasm_test::{ext_reset};
 
@@ -16,6 +16,15 @@ const PAYLOAD_BOO: &[u8] = &[
 	0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x45,
 ];
 
+// Error("Unable to accept value in non-payable constructor call")
+const REVERT_NON_PAYABLE: &[u8] = &[
+	0x08, 0xc3, 0x79, 0xa0,
+	0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x20,
+	0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x36,
+	b'U', b'n', b'a', b'b', b'l', b'e', b' ', b't', b'o', b' ', b'a', b'c', b'c', b'e', b'p', b't', b' ', b'v', b'a', b'l', b'u', b'e', b' ', b'i', b'n', b' ', b'n', b'o', b'n', b'-', b'p', b'a',
+	b'y', b'a', b'b', b'l', b'e', b' ', b'c', b'o', b'n', b's', b't', b'r', b'u', b'c', b't', b'o', b'r', b' ', b'c', b'a', b'l', b'l', 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+];
+
 #[eth_abi(NonPayableEndpoint)]
 pub trait NonPayableContract {
 	fn constructor(&mut self);
@@ -32,7 +41,7 @@ impl NonPayableContract for NonPayableContractInstance {
 }
 
 #[test]
-#[should_panic]
+#[should_panic(expected = "Unable to accept value in non-payable constructor call")]
 fn non_payable_constructor_value() {
 	ext_reset(|e| e.value(1.into()));
 	NonPayableEndpoint::new(NonPayableContractInstance).dispatch_ctor(&[]);
@@ -52,6 +61,15 @@ fn non_payable_with_ret_value() {
 	NonPayableEndpoint::new(NonPayableContractInstance).dispatch(PAYLOAD_BOO);
 }
 
+#[test]
+fn non_payable_revert_payload() {
+	ext_reset(|e| e.value(1.into()));
+	let mut endpoint = NonPayableEndpoint::new(NonPayableContractInstance);
+
+	assert_eq!(endpoint.try_dispatch_ctor(&[]), Err(Revert::new(REVERT_NON_PAYABLE.to_vec())));
+	assert_eq!(endpoint.try_dispatch(PAYLOAD_BOO), Err(Revert::new(REVERT_NON_PAYABLE.to_vec())));
+}
+
 #[test]
 fn non_payable_constructor_no_value() {
 	NonPayableEndpoint::new(NonPayableContractInstance).dispatch_ctor(&[]);
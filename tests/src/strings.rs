@@ -0,0 +1,138 @@
+#![allow(dead_code)]
+
+use pwasm_test::{ext_get, ext_reset};
+use owasm_abi::eth::{AsLog, EndpointInterface};
+use owasm_abi::types::*;
+use owasm_abi_derive::eth_abi;
+
+#[eth_abi(StringEndpoint, StringClient)]
+pub trait StringContract {
+	fn greet(&mut self, name: String) -> String;
+	fn shout(&mut self, name: &str) -> String;
+	fn byte_sum(&mut self, data: &[u8]) -> u64;
+
+	#[event]
+	fn greeted(&mut self, indexed_name: &str, greeting: &str);
+}
+
+// greet("dave")
+const PAYLOAD_SAMPLE_1: &[u8] = &[
+	0xea, 0xd7, 0x10, 0xc4,
+	0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x20,
+	0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x04,
+	0x64, 0x61, 0x76, 0x65, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+];
+
+// shout("dave")
+const PAYLOAD_SAMPLE_2: &[u8] = &[
+	0x62, 0x5d, 0x20, 0x55,
+	0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x20,
+	0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x04,
+	0x64, 0x61, 0x76, 0x65, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+];
+
+// byte_sum([1, 2, 3])
+const PAYLOAD_SAMPLE_3: &[u8] = &[
+	0x50, 0xe2, 0xc6, 0xf8,
+	0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x20,
+	0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03,
+	0x01, 0x02, 0x03, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+];
+
+pub struct Instance;
+
+impl StringContract for Instance {
+	fn greet(&mut self, name: String) -> String {
+		format!("hello {}", name)
+	}
+
+	fn shout(&mut self, name: &str) -> String {
+		let greeting = format!("HELLO {}", name.to_uppercase());
+		self.greeted(name, &greeting);
+		greeting
+	}
+
+	fn byte_sum(&mut self, data: &[u8]) -> u64 {
+		data.iter().map(|b| *b as u64).sum()
+	}
+}
+
+#[test]
+fn greet() {
+	let mut endpoint = StringEndpoint::new(Instance);
+	let result = endpoint.dispatch(PAYLOAD_SAMPLE_1);
+
+	assert_eq!(&result[..], &[
+		0u8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x20,
+		0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 10,
+		b'h', b'e', b'l', b'l', b'o', b' ', b'd', b'a', b'v', b'e', 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0
+	][..]);
+}
+
+#[test]
+fn shout_borrowed() {
+	ext_reset(|e| e);
+	let mut endpoint = StringEndpoint::new(Instance);
+	let result = endpoint.dispatch(PAYLOAD_SAMPLE_2);
+
+	assert_eq!(&result[..], &[
+		0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x20,
+		0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0a,
+		0x48, 0x45, 0x4c, 0x4c, 0x4f, 0x20, 0x44, 0x41, 0x56, 0x45, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+	][..]);
+
+	let ext = ext_get();
+	let logs = ext.logs();
+	assert_eq!(logs.len(), 1);
+	assert_eq!(logs[0].topics[1], "dave".as_log());
+	assert_eq!(&logs[0].data[..], &[
+		0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x20,
+		0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0a,
+		0x48, 0x45, 0x4c, 0x4c, 0x4f, 0x20, 0x44, 0x41, 0x56, 0x45, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+	][..]);
+}
+
+#[test]
+fn greeted_decoded() {
+	ext_reset(|e| e);
+	let mut endpoint = StringEndpoint::new(Instance);
+	endpoint.dispatch(PAYLOAD_SAMPLE_2);
+
+	let ext = ext_get();
+	let logs = ext.logs();
+	assert_eq!(logs[0].topics[0].as_ref(), &GreetedEvent::SIGNATURE_HASH[..]);
+
+	// indexed strings are only known by their hash
+	let event = GreetedEvent::decode(&logs[0].topics, &logs[0].data).unwrap();
+	assert_eq!(event.name, "dave".as_log());
+	assert_eq!(event.greeting, "HELLO DAVE");
+
+	assert_eq!(
+		GreetedEvent::decode(&logs[0].topics[..1], &logs[0].data).err(),
+		Some(owasm_abi::eth::Error::InvalidTopic)
+	);
+	let mut topics = logs[0].topics.to_vec();
+	topics[0] = H256::zero();
+	assert_eq!(
+		GreetedEvent::decode(&topics, &logs[0].data).err(),
+		Some(owasm_abi::eth::Error::InvalidTopic)
+	);
+}
+
+#[test]
+fn byte_sum_borrowed() {
+	let mut endpoint = StringEndpoint::new(Instance);
+	let result = endpoint.dispatch(PAYLOAD_SAMPLE_3);
+
+	assert_eq!(&result[..], &[
+		0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x06,
+	][..]);
+}
+
+#[test]
+fn shout_call() {
+	ext_reset(|e| e.endpoint(Address::zero(), ::pwasm_test::Endpoint::ok()));
+	let mut client = StringClient::new(Address::zero());
+	client.shout("dave");
+	assert_eq!(ext_get().calls()[0].input.as_ref(), PAYLOAD_SAMPLE_2);
+}
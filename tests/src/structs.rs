@@ -0,0 +1,219 @@
+#![allow(dead_code)]
+
+use pwasm_test::{ext_get, ext_reset};
+use owasm_abi::eth::{encode_into, AbiType, AsLog, EndpointInterface};
+use owasm_abi::types::*;
+use owasm_abi_derive::{eth_abi, AbiType};
+
+#[derive(AbiType, Debug, PartialEq)]
+pub struct Payment {
+	amount: U256,
+	to: Address,
+}
+
+#[derive(AbiType, Debug, PartialEq)]
+pub struct Note {
+	id: u64,
+	text: String,
+}
+
+#[eth_abi(StructEndpoint, StructClient)]
+pub trait StructContract {
+	fn pay(&mut self, payment: Payment) -> U256;
+	fn annotate(&mut self, note: Note, flag: bool) -> Note;
+	fn total(&mut self, receipt: Receipt) -> u64;
+
+	#[event]
+	fn paid(&mut self, indexed_to: Address, payment: Payment);
+}
+
+// declared after the interface using it
+#[derive(AbiType, Debug, PartialEq)]
+pub struct Receipt {
+	id: u64,
+	payments: Vec<Payment>,
+}
+
+mod other {
+	use owasm_abi_derive::AbiType;
+
+	// same name as the `Note` of the interface, with other members
+	#[derive(AbiType)]
+	pub struct Note {
+		id: u8,
+	}
+}
+
+// pay((1000, 0x1111111111111111111111111111111111111111))
+const PAYLOAD_SAMPLE_1: &[u8] = &[
+	0x22, 0x34, 0xf7, 0x5f,
+	0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03, 0xe8,
+	0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11,
+];
+
+// annotate((7, "hi"), true)
+const PAYLOAD_SAMPLE_2: &[u8] = &[
+	0x14, 0x7a, 0x48, 0x90,
+	0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x40,
+	0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01,
+	0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x07,
+	0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x40,
+	0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02,
+	0x68, 0x69, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+];
+
+// total((5, []))
+const PAYLOAD_SAMPLE_3: &[u8] = &[
+	0xa2, 0xb9, 0xde, 0xe4,
+	0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x20,
+	0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x05,
+	0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x40,
+	0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+];
+
+pub struct Instance;
+
+impl StructContract for Instance {
+	fn pay(&mut self, payment: Payment) -> U256 {
+		let amount = payment.amount;
+		self.paid(payment.to, payment);
+		amount * U256::from(2)
+	}
+
+	fn annotate(&mut self, note: Note, flag: bool) -> Note {
+		assert!(flag);
+		Note { id: note.id + 1, text: note.text + "!" }
+	}
+
+	fn total(&mut self, receipt: Receipt) -> u64 {
+		receipt.id + receipt.payments.len() as u64
+	}
+}
+
+#[test]
+fn pay_static_struct() {
+	ext_reset(|e| e);
+	let mut endpoint = StructEndpoint::new(Instance);
+	let result = endpoint.dispatch(PAYLOAD_SAMPLE_1);
+
+	assert_eq!(&result[..], &[
+		0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x07, 0xd0,
+	][..]);
+
+	let ext = ext_get();
+	let logs = ext.logs();
+	assert_eq!(logs.len(), 1);
+	assert_eq!(logs[0].topics[1], Address::from([0x11u8; 20]).into());
+	assert_eq!(&logs[0].data[..], &[
+		0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03, 0xe8,
+		0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11,
+	][..]);
+}
+
+#[test]
+fn paid_decoded() {
+	ext_reset(|e| e);
+	let mut endpoint = StructEndpoint::new(Instance);
+	endpoint.dispatch(PAYLOAD_SAMPLE_1);
+
+	let ext = ext_get();
+	let logs = ext.logs();
+	let event = PaidEvent::decode(&logs[0].topics, &logs[0].data).unwrap();
+	assert_eq!(event.to, Address::from([0x11u8; 20]));
+	assert_eq!(event.payment, Payment { amount: U256::from(1000), to: Address::from([0x11u8; 20]) });
+}
+
+#[test]
+fn annotate_dynamic_struct() {
+	let mut endpoint = StructEndpoint::new(Instance);
+	let result = endpoint.dispatch(PAYLOAD_SAMPLE_2);
+
+	assert_eq!(&result[..], &[
+		0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x20,
+		0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x08,
+		0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x40,
+		0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03,
+		0x68, 0x69, 0x21, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+	][..]);
+}
+
+#[test]
+fn annotate_into_buffer() {
+	let mut endpoint = StructEndpoint::new(Instance);
+	let result = endpoint.dispatch(PAYLOAD_SAMPLE_2);
+
+	let mut buffer = [0u8; 160];
+	let len = encode_into(&Note { id: 8, text: "hi!".to_owned() }, &mut buffer).unwrap();
+	assert_eq!(&buffer[..len], &result[..]);
+}
+
+#[test]
+fn dynamic_structs_as_log() {
+	// members of dynamic structs are encoded in place, without offsets and lengths
+	let mut in_place = [0u8; 64];
+	in_place[31] = 8;
+	in_place[32..35].copy_from_slice(b"hi!");
+	let notes = vec![Note { id: 8, text: "hi!".to_owned() }];
+	assert_eq!(notes.as_log(), in_place[..].as_log());
+}
+
+#[test]
+fn struct_call() {
+	ext_reset(|e| e.endpoint(Address::zero(), ::pwasm_test::Endpoint::ok()));
+	let mut client = StructClient::new(Address::zero());
+	client.pay(Payment { amount: 1000.into(), to: Address::from([0x11u8; 20]) });
+	assert_eq!(ext_get().calls()[0].input.as_ref(), PAYLOAD_SAMPLE_1);
+}
+
+#[test]
+fn canonical_types() {
+	assert_eq!(<Payment as AbiType>::CANONICAL.to_string(), "(uint256,address)");
+	assert_eq!(<Receipt as AbiType>::CANONICAL.to_string(), "(uint64,(uint256,address)[])");
+	assert_eq!(<Note as AbiType>::CANONICAL.to_string(), "(uint64,string)");
+	assert_eq!(<other::Note as AbiType>::CANONICAL.to_string(), "(uint8)");
+}
+
+#[test]
+fn total_struct_declared_later() {
+	let mut endpoint = StructEndpoint::new(Instance);
+	let result = endpoint.dispatch(PAYLOAD_SAMPLE_3);
+
+	assert_eq!(&result[..], &[
+		0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x05,
+	][..]);
+}
+
+#[test]
+fn json_abi_tuples() {
+	let abi = owasm_abi::eth::json_abi(StructEndpoint::<Instance>::JSON_ABI);
+
+	assert!(abi.contains(r#"
+    "name": "total",
+    "inputs": [
+      {
+        "name": "receipt",
+        "type": "tuple",
+        "components": [
+          {
+            "name": "id",
+            "type": "uint64"
+          },
+          {
+            "name": "payments",
+            "type": "tuple[]",
+            "components": [
+              {
+                "name": "amount",
+                "type": "uint256"
+              },
+              {
+                "name": "to",
+                "type": "address"
+              }
+            ]
+          }
+        ]
+      }
+    ],"#));
+	assert!(!abi.contains("Payment"));
+}